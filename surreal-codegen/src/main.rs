@@ -28,10 +28,8 @@ struct Cli {
 
     /// Header to add to the top of the output file
     /// If you specify this, you must import in RecordId type and a Surreal class that has a .query(query: string, variables?: Record<string, unknown>) method
-    #[clap(
-        long,
-        default_value = "import { type RecordId, Surreal } from 'surrealdb'"
-    )]
+    /// If your schema uses geometry types, you must also import the Geometry and GeometryPoint types
+    #[clap(long, default_value = step_3_codegen::typescript::DEFAULT_HEADER)]
     header: String,
 }
 
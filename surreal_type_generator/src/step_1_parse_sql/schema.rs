@@ -3,11 +3,11 @@ use std::collections::BTreeMap;
 use surrealdb::sql::{
     parse,
     statements::{
-        DefineFieldStatement, DefineFunctionStatement, DefineStatement, DefineTableStatement,
-        IfelseStatement, ThrowStatement,
+        DefineFieldStatement, DefineFunctionStatement, DefineParamStatement, DefineStatement,
+        DefineTableStatement, ForeachStatement, IfelseStatement, ThrowStatement,
     },
-    Block, Entry, Expression, Fields, Function, Groups, Idiom, Kind, Param, Part, Query, Statement,
-    Tables, Value,
+    Block, Entry, Expression, Fields, Function, Groups, Idiom, Kind, Literal, Param, Part, Query,
+    Relation, Statement, TableType, Tables, Value,
 };
 
 use crate::kind;
@@ -17,6 +17,30 @@ pub struct SchemaParsed {
     pub tables: BTreeMap<String, TableParsed>,
     pub functions: BTreeMap<String, FunctionParsed>,
     pub views: BTreeMap<String, ViewParsed>,
+    /// `DEFINE PARAM` statements, in the order they were defined
+    pub params: Vec<ParamParsed>,
+}
+
+impl SchemaParsed {
+    /// Check whether an object is a record of a `SCHEMALESS` table, going by its `id` field,
+    /// in which case it can also contain fields that aren't defined in the schema
+    pub fn is_schemaless_record(&self, fields: &BTreeMap<String, Kind>) -> bool {
+        let tables = match fields.get("id") {
+            Some(Kind::Record(tables)) => tables,
+            Some(Kind::Option(inner)) => match &**inner {
+                Kind::Record(tables) => tables,
+                _ => return false,
+            },
+            _ => return false,
+        };
+
+        tables
+            .iter()
+            .any(|table| match self.tables.get(table.as_str()) {
+                Some(table) => !table.schemafull,
+                None => false,
+            })
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -33,6 +57,14 @@ pub struct FunctionParsed {
     pub name: String,
     pub arguments: Vec<(String, Kind)>,
     pub block: Block,
+    /// The return type declared with `-> type`, if any
+    pub return_type: Option<Kind>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamParsed {
+    pub name: String,
+    pub value: Value,
 }
 
 #[derive(Debug, PartialEq)]
@@ -46,6 +78,8 @@ pub enum FieldType {
 #[derive(Debug, PartialEq)]
 pub struct TableParsed {
     pub name: String,
+    pub table_type: TableType,
+    pub schemafull: bool,
     pub id_value_type: Kind,
     pub fields: BTreeMap<String, FieldParsed>,
 }
@@ -64,6 +98,17 @@ pub struct FieldParsed {
 
 impl FieldParsed {
     pub fn compute_create_type(&self) -> anyhow::Result<Kind> {
+        self.compute_input_type(&|field| !field.has_override_value)
+    }
+
+    /// The type of the field when replacing a record with `UPDATE ... CONTENT`,
+    /// which can't change `READONLY` fields or fields with a `VALUE` clause
+    pub fn compute_update_type(&self) -> anyhow::Result<Kind> {
+        self.compute_input_type(&|field| !field.has_override_value && !field.readonly)
+    }
+
+    /// The type of the field when writing to it, only including nested fields that match `include`
+    fn compute_input_type(&self, include: &dyn Fn(&FieldParsed) -> bool) -> anyhow::Result<Kind> {
         Ok(match &self.field_type {
             FieldType::Simple => match self.is_optional || self.has_default {
                 true => Kind::Option(Box::new(self.return_type.clone())),
@@ -72,8 +117,8 @@ impl FieldParsed {
             FieldType::NestedObject(obj) => {
                 let mut fields = BTreeMap::new();
                 for (key, value) in obj {
-                    if !value.has_override_value {
-                        fields.insert(key.clone(), value.compute_create_type()?);
+                    if include(value) {
+                        fields.insert(key.clone(), value.compute_input_type(include)?);
                     }
                 }
 
@@ -84,7 +129,29 @@ impl FieldParsed {
                     false => fields,
                 }
             }
-            _ => anyhow::bail!("TODO: Unsupported field type: {:?}", self.field_type),
+            FieldType::NestedArray(box inner_type) => {
+                let input_type = match inner_type {
+                    FieldType::Simple => self.return_type.clone(),
+                    FieldType::NestedObject(fields) => {
+                        let mut input_fields = BTreeMap::new();
+                        for (key, value) in fields {
+                            if include(value) {
+                                input_fields
+                                    .insert(key.clone(), value.compute_input_type(include)?);
+                            }
+                        }
+                        kind!(Obj input_fields)
+                    }
+                    FieldType::NestedArray(..) => {
+                        anyhow::bail!("Nested array in nested array are not yet supported")
+                    }
+                };
+
+                match self.is_optional || self.has_default {
+                    true => kind!(Opt(kind!([input_type]))),
+                    false => kind!(Arr input_type),
+                }
+            }
         })
     }
 
@@ -129,12 +196,6 @@ impl FieldParsed {
             }
         })
     }
-
-    pub fn compute_update_type(&self) -> anyhow::Result<Kind> {
-        anyhow::bail!("TODO: Query interpretation for UPDATE statements is not yet supported")
-        // return both flatten types
-        // ignore readonlys
-    }
 }
 
 impl TableParsed {
@@ -148,6 +209,37 @@ impl TableParsed {
         Ok(fields)
     }
 
+    /// The fields that can be written with `RELATE`, where `in` and `out` are always set by
+    /// the statement itself
+    pub fn compute_relate_create_fields(&self) -> anyhow::Result<BTreeMap<String, Kind>> {
+        let mut fields = self.compute_create_fields()?;
+
+        fields.remove("in");
+        fields.remove("out");
+
+        Ok(fields)
+    }
+
+    /// The fields that can be written with `INSERT RELATION`, where `in` and `out` are required
+    pub fn compute_relation_create_fields(&self) -> anyhow::Result<BTreeMap<String, Kind>> {
+        if let TableType::Normal = self.table_type {
+            anyhow::bail!("Table `{}` is not a relation table", self.name)
+        }
+
+        let mut fields = self.compute_create_fields()?;
+
+        for name in ["in", "out"] {
+            let kind = match fields.remove(name) {
+                Some(Kind::Option(inner)) => *inner,
+                Some(kind) => kind,
+                None => Kind::Record(vec![]),
+            };
+            fields.insert(name.into(), kind);
+        }
+
+        Ok(fields)
+    }
+
     pub fn compute_select_fields(&self) -> anyhow::Result<BTreeMap<String, Kind>> {
         let mut fields = BTreeMap::new();
         for (key, value) in &self.fields {
@@ -156,13 +248,256 @@ impl TableParsed {
         Ok(fields)
     }
 
+    /// The fields that can be replaced with `UPDATE ... CONTENT`
     pub fn compute_update_fields(&self) -> anyhow::Result<BTreeMap<String, Kind>> {
         let mut fields = BTreeMap::new();
         for (key, value) in &self.fields {
-            fields.insert(key.clone(), value.compute_update_type()?);
+            if !value.has_override_value && !value.readonly {
+                fields.insert(key.clone(), value.compute_update_type()?);
+            }
+        }
+        // the `id` can be passed as long as it is unchanged, which keeps the
+        // fields of schemaless tables recognisable as an open record
+        if !self.schemafull {
+            if let Some(id) = self.fields.get("id") {
+                fields.insert("id".into(), id.compute_create_type()?);
+            }
         }
         Ok(fields)
     }
+
+    /// The fields that can be merged with `UPDATE ... MERGE`, where every field,
+    /// including nested fields, is optional
+    pub fn compute_merge_fields(&self) -> anyhow::Result<BTreeMap<String, Kind>> {
+        Ok(into_partial_fields(self.compute_update_fields()?))
+    }
+
+    /// The fields that can be merged with `UPSERT ... MERGE`, which can also create the record,
+    /// so readonly fields can be set as well
+    pub fn compute_upsert_merge_fields(&self) -> anyhow::Result<BTreeMap<String, Kind>> {
+        Ok(into_partial_fields(self.compute_create_fields()?))
+    }
+
+    /// The JSON Patch operations that can be applied with `UPDATE ... PATCH`, where each
+    /// operation's `path` is a field of the table, and its `value` is the type of that field
+    pub fn compute_patch_operations(&self) -> anyhow::Result<Vec<Kind>> {
+        let mut fields = self.compute_update_fields()?;
+        // the `id` of a record can never be patched
+        fields.remove("id");
+
+        let mut paths = Vec::new();
+        collect_field_paths("", &fields, &mut paths);
+
+        if paths.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut operations = Vec::new();
+
+        for (path, kind) in paths.iter() {
+            operations.push(kind!({
+                op: literal_union(["add", "replace", "test"]),
+                path: literal_union([path.as_str()]),
+                value: kind.clone()
+            }));
+        }
+
+        // only optional fields can be removed without failing the record's schema
+        let removable = paths
+            .iter()
+            .filter(|(_, kind)| matches!(kind, Kind::Option(_)))
+            .map(|(path, _)| path.as_str())
+            .collect::<Vec<_>>();
+
+        if !removable.is_empty() {
+            operations.push(kind!({
+                op: literal_union(["remove"]),
+                path: literal_union(removable)
+            }));
+        }
+
+        let all_paths = paths
+            .iter()
+            .map(|(path, _)| path.as_str())
+            .collect::<Vec<_>>();
+
+        operations.push(kind!({
+            op: literal_union(["copy", "move"]),
+            from: literal_union(all_paths.clone()),
+            path: literal_union(all_paths)
+        }));
+
+        Ok(operations)
+    }
+
+    /// The JSON Patch operations returned by `RETURN DIFF` when a record of this table is
+    /// changed, where `change` operations are text diffs of string fields
+    pub fn compute_diff_operations(&self, change: RecordChange) -> anyhow::Result<Vec<Kind>> {
+        let mut fields = self.compute_select_fields()?;
+        let mut operations = Vec::new();
+
+        // a record that didn't exist, or no longer exists, is diffed as a whole
+        match change {
+            RecordChange::Create | RecordChange::Upsert => operations.push(kind!({
+                op: literal_union(["replace"]),
+                path: literal_union(["/"]),
+                value: kind!(Obj fields.clone())
+            })),
+            RecordChange::Delete => operations.push(kind!({
+                op: literal_union(["replace"]),
+                path: literal_union(["/"]),
+                value: kind!(Null)
+            })),
+            RecordChange::Update => {}
+        }
+
+        if matches!(change, RecordChange::Create | RecordChange::Delete) {
+            return Ok(operations);
+        }
+
+        // the id of a record can never change
+        fields.remove("id");
+
+        let mut paths = Vec::new();
+        collect_field_paths("", &fields, &mut paths);
+
+        for (path, kind) in paths.iter() {
+            // fields which are set to `NONE` are removed rather than replaced
+            let value = match kind {
+                Kind::Option(inner) => *inner.clone(),
+                kind => kind.clone(),
+            };
+
+            operations.push(kind!({
+                op: literal_union(["add", "replace"]),
+                path: literal_union([path.as_str()]),
+                value: value
+            }));
+        }
+
+        let filter_paths = |filter: fn(&Kind) -> bool| {
+            paths
+                .iter()
+                .filter(|(_, kind)| filter(kind))
+                .map(|(path, _)| path.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        let changed = filter_paths(|kind| match kind {
+            Kind::Option(inner) => matches!(**inner, Kind::String),
+            kind => matches!(kind, Kind::String),
+        });
+
+        if !changed.is_empty() {
+            operations.push(kind!({
+                op: literal_union(["change"]),
+                path: literal_union(changed),
+                value: kind!(String)
+            }));
+        }
+
+        let removable = filter_paths(|kind| matches!(kind, Kind::Option(_)));
+
+        if !removable.is_empty() {
+            operations.push(kind!({
+                op: literal_union(["remove"]),
+                path: literal_union(removable)
+            }));
+        }
+
+        // array items and the fields of untyped objects are diffed with paths we can't know ahead of time
+        if !self.schemafull || paths.iter().any(|(_, kind)| has_dynamic_paths(kind)) {
+            operations.push(kind!({
+                op: literal_union(["add", "remove", "replace", "change"]),
+                path: kind!(String),
+                value: kind!(Opt(kind!(Any)))
+            }));
+        }
+
+        Ok(operations)
+    }
+}
+
+/// How a statement changes the records it writes to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecordChange {
+    /// The record is created, such as with `CREATE` or `RELATE`
+    Create,
+    /// An existing record is modified
+    Update,
+    /// The record is either created or modified
+    Upsert,
+    /// The record is deleted
+    Delete,
+}
+
+/// Whether a field's type has children that aren't known ahead of time, such as array items
+fn has_dynamic_paths(kind: &Kind) -> bool {
+    match kind {
+        Kind::Option(inner) => has_dynamic_paths(inner),
+        Kind::Either(kinds) => kinds.iter().any(has_dynamic_paths),
+        Kind::Any | Kind::Object | Kind::Array(..) | Kind::Set(..) => true,
+        Kind::Literal(Literal::Array(_)) => true,
+        _ => false,
+    }
+}
+
+/// Collect the JSON Pointer paths of every field, including nested fields, eg: `/address/city`
+fn collect_field_paths(
+    prefix: &str,
+    fields: &BTreeMap<String, Kind>,
+    paths: &mut Vec<(String, Kind)>,
+) {
+    for (key, kind) in fields {
+        let path = format!("{}/{}", prefix, key.replace('~', "~0").replace('/', "~1"));
+
+        paths.push((path.clone(), kind.clone()));
+
+        let inner = match kind {
+            Kind::Option(inner) => inner.as_ref(),
+            kind => kind,
+        };
+
+        if let Kind::Literal(Literal::Object(fields)) = inner {
+            collect_field_paths(&path, fields, paths);
+        }
+    }
+}
+
+/// A union of string literals, or a single string literal
+fn literal_union<'a>(strings: impl IntoIterator<Item = &'a str>) -> Kind {
+    let mut kinds = strings
+        .into_iter()
+        .map(|string| Kind::Literal(Literal::String(string.into())))
+        .collect::<Vec<_>>();
+
+    match kinds.len() {
+        1 => kinds.pop().unwrap(),
+        _ => Kind::Either(kinds),
+    }
+}
+
+fn into_partial_fields(fields: BTreeMap<String, Kind>) -> BTreeMap<String, Kind> {
+    fields
+        .into_iter()
+        .map(|(key, value)| (key, into_deep_partial(value)))
+        .collect()
+}
+
+/// Make a kind optional, along with every field of any nested objects
+fn into_deep_partial(kind: Kind) -> Kind {
+    match kind {
+        Kind::Option(inner) => into_deep_partial(*inner),
+        Kind::Literal(Literal::Object(fields)) => {
+            let fields = fields
+                .into_iter()
+                .map(|(key, value)| (key, into_deep_partial(value)))
+                .collect();
+
+            kind!(Opt(kind!(Obj fields)))
+        }
+        kind => kind!(Opt(kind)),
+    }
 }
 
 fn parse_table(
@@ -184,6 +519,26 @@ fn parse_table(
         },
     )]);
 
+    // insert the implicit `in` and `out` fields of relation tables
+    if let TableType::Relation(Relation { from, to, .. }) = &table.kind {
+        for (name, kind) in [("in", from), ("out", to)] {
+            fields.insert(
+                name.into(),
+                FieldParsed {
+                    name: name.into(),
+                    is_optional: false,
+                    field_type: FieldType::Simple,
+                    has_default: false,
+                    has_override_value: false,
+                    readonly: true,
+                    flexible: false,
+                    // `TYPE RELATION` without `IN` or `OUT` can link any record
+                    return_type: kind.clone().unwrap_or(Kind::Record(vec![])),
+                },
+            );
+        }
+    }
+
     for (idiom, field) in field_definitions {
         let return_type = match &field.kind {
             Some(kind) => kind,
@@ -250,6 +605,8 @@ fn parse_table(
 
     return Ok(TableParsed {
         name: table.name.to_string(),
+        table_type: table.kind.clone(),
+        schemafull: table.full,
         id_value_type,
         fields,
     });
@@ -348,7 +705,9 @@ fn entry_uses_value_param(entry: &Entry) -> Result<bool, anyhow::Error> {
         Entry::Output(_) => anyhow::bail!("Output statements not supported in VALUE clause yet"),
         Entry::Set(_) => anyhow::bail!("LET $var statements not supported in VALUE clause yet"),
         Entry::Select(_) => anyhow::bail!("Select statements not supported in VALUE clause yet"),
-        Entry::Foreach(_) => anyhow::bail!("Foreach statements not supported in VALUE clause yet"),
+        Entry::Foreach(ForeachStatement { range, block, .. }) => {
+            value_uses_value_param(range)? || block_uses_value_param(block)?
+        }
         Entry::Upsert(_) => anyhow::bail!("Upsert statements not supported in VALUE clause yet"),
         Entry::Define(_) => anyhow::bail!("Define statements not supported in VALUE clause yet"),
         Entry::Remove(_) => anyhow::bail!("Remove statements not supported in VALUE clause yet"),
@@ -376,6 +735,7 @@ pub fn parse_schema(schema: &str) -> Result<SchemaParsed, anyhow::Error> {
     let mut tables = BTreeMap::new();
     let mut views = BTreeMap::new();
     let mut functions = BTreeMap::new();
+    let mut params = Vec::new();
 
     for stmt in statements.into_iter() {
         match stmt {
@@ -424,6 +784,7 @@ pub fn parse_schema(schema: &str) -> Result<SchemaParsed, anyhow::Error> {
                 name,
                 args,
                 block,
+                returns,
                 ..
             })) => {
                 functions.insert(
@@ -435,9 +796,18 @@ pub fn parse_schema(schema: &str) -> Result<SchemaParsed, anyhow::Error> {
                             .map(|(ident, kind)| Ok((ident.to_string(), kind.clone())))
                             .collect::<Result<Vec<(String, Kind)>, anyhow::Error>>()?,
                         block: block.clone(),
+                        return_type: returns,
                     },
                 );
             }
+            Statement::Define(DefineStatement::Param(DefineParamStatement {
+                name, value, ..
+            })) => {
+                params.push(ParamParsed {
+                    name: name.to_string(),
+                    value,
+                });
+            }
             // ignore other statements
             _ => {}
         }
@@ -455,6 +825,7 @@ pub fn parse_schema(schema: &str) -> Result<SchemaParsed, anyhow::Error> {
         tables,
         functions,
         views,
+        params,
     });
 }
 
@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+
+use surrealdb::sql::{Block, Entry};
+
+use crate::Kind;
+
+use super::{
+    get_create_statement_return_type, get_delete_statement_return_type, get_foreach_statement_flow,
+    get_ifelse_statement_flow, get_insert_statement_return_type, get_relate_statement_return_type,
+    get_return_statement_return_type, get_select_statement_return_type,
+    get_update_statement_return_type, get_upsert_statement_return_type, interpret_let_statement,
+    return_types::get_value_return_type,
+    schema::QueryState,
+    utils::{never, widen_union},
+};
+
+/// How control can leave a block, or a statement within a block.
+#[derive(Debug, Default)]
+pub struct BlockFlow {
+    /// The types of every `RETURN` that can be reached
+    pub returns: Vec<Kind>,
+    /// The type the block evaluates to when it runs to completion, or `None` when
+    /// it always exits early with `RETURN`, `THROW`, `BREAK` or `CONTINUE`
+    pub completes: Option<Kind>,
+}
+
+impl BlockFlow {
+    pub fn completes(kind: Kind) -> Self {
+        Self {
+            returns: Vec::new(),
+            completes: Some(kind),
+        }
+    }
+
+    /// The type of the block when used as a value, where any `RETURN` is caught by the block itself
+    pub fn into_return_type(self) -> Kind {
+        let mut kinds = self.returns;
+        kinds.extend(self.completes);
+
+        match kinds.is_empty() {
+            // the block can never return, eg: it always throws
+            true => never(),
+            false => widen_union(kinds),
+        }
+    }
+}
+
+/// Interpret a `{ ... }` block in its own stack frame, so that any `LET` bindings
+/// made inside of it don't leak into the surrounding scope.
+pub fn get_block_return_type(block: &Block, state: &mut QueryState) -> Result<Kind, anyhow::Error> {
+    Ok(get_block_flow(block, state)?.into_return_type())
+}
+
+pub fn get_block_flow(block: &Block, state: &mut QueryState) -> Result<BlockFlow, anyhow::Error> {
+    state.push_stack_frame();
+
+    let flow = get_block_entries_flow(block, state);
+
+    state.pop_stack_frame();
+
+    flow
+}
+
+/// A block evaluates to its trailing expression, or `NONE` if the last entry
+/// is a statement. A `RETURN` anywhere within the block, including inside of
+/// nested `IF` and `FOR` statements, exits it early with its value.
+fn get_block_entries_flow(
+    block: &Block,
+    state: &mut QueryState,
+) -> Result<BlockFlow, anyhow::Error> {
+    let mut flow = BlockFlow::default();
+
+    for (i, entry) in block.iter().enumerate() {
+        let is_last = i == block.len() - 1;
+
+        match entry {
+            Entry::Output(output) => {
+                flow.returns
+                    .push(get_return_statement_return_type(output, state)?);
+                return Ok(flow);
+            }
+            Entry::Throw(throw) => {
+                get_value_return_type(&throw.error, &BTreeMap::new(), state)?;
+                return Ok(flow);
+            }
+            Entry::Break(_) | Entry::Continue(_) => return Ok(flow),
+            Entry::Ifelse(ifelse) => {
+                let ifelse_flow = get_ifelse_statement_flow(ifelse, state)?;
+                flow.returns.extend(ifelse_flow.returns);
+
+                // every branch exits early, so the rest of the block is unreachable
+                if ifelse_flow.completes.is_none() {
+                    return Ok(flow);
+                }
+            }
+            Entry::Foreach(foreach) => {
+                let foreach_flow = get_foreach_statement_flow(foreach, state)?;
+                flow.returns.extend(foreach_flow.returns);
+            }
+            Entry::Value(value) => {
+                let return_type = get_value_return_type(value, &BTreeMap::new(), state)?;
+
+                if is_last {
+                    flow.completes = Some(return_type);
+                    return Ok(flow);
+                }
+            }
+            entry => {
+                get_entry_return_type(entry, state)?;
+            }
+        }
+    }
+
+    flow.completes = Some(Kind::Null);
+
+    Ok(flow)
+}
+
+fn get_entry_return_type(entry: &Entry, state: &mut QueryState) -> Result<Kind, anyhow::Error> {
+    match entry {
+        Entry::Set(set) => interpret_let_statement(set, state),
+        Entry::Select(select) => get_select_statement_return_type(select, state),
+        Entry::Create(create) => get_create_statement_return_type(create, state),
+        Entry::Update(update) => get_update_statement_return_type(update, state),
+        Entry::Upsert(upsert) => get_upsert_statement_return_type(upsert, state),
+        Entry::Delete(delete) => get_delete_statement_return_type(delete, state),
+        Entry::Insert(insert) => get_insert_statement_return_type(insert, state),
+        Entry::Relate(relate) => get_relate_statement_return_type(relate, state),
+        _ => anyhow::bail!("Unsupported block entry: `{}`", entry),
+    }
+}
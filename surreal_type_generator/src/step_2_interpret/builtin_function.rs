@@ -0,0 +1,539 @@
+use surrealdb::sql::{Literal, Value};
+
+use crate::{kind, Kind};
+
+use super::{
+    schema::QueryState,
+    utils::{get_array_element_type, get_number_kind, optional, union_kinds},
+};
+
+/// The signature of a built-in function, such as `string::len` or `array::first`
+pub struct FunctionSignature {
+    /// The types of the arguments, used to infer the types of untyped parameters
+    pub args: Vec<Kind>,
+    /// The return type, which for generic functions depends on the types of the arguments,
+    /// eg: `array::first(array<T>) -> option<T>`
+    pub return_type: Kind,
+    /// The type of any further arguments, for functions that take any number of arguments,
+    /// eg: `string::concat(...values)`
+    pub rest: Option<Kind>,
+}
+
+impl FunctionSignature {
+    /// Get the type of the argument at an index, if the function accepts one there
+    pub fn arg(&self, index: usize) -> Option<&Kind> {
+        self.args.get(index).or(self.rest.as_ref())
+    }
+}
+
+fn sig(args: Vec<Kind>, return_type: Kind) -> FunctionSignature {
+    FunctionSignature {
+        args,
+        return_type,
+        rest: None,
+    }
+}
+
+fn variadic(args: Vec<Kind>, rest: Kind, return_type: Kind) -> FunctionSignature {
+    FunctionSignature {
+        args,
+        return_type,
+        rest: Some(rest),
+    }
+}
+
+/// Get the signature of a built-in function from the SurrealDB 2.x standard library,
+/// given the values passed to it and their types
+pub fn get_builtin_function_signature(
+    name: &str,
+    values: &[Value],
+    args: &[Kind],
+    state: &QueryState,
+) -> Result<FunctionSignature, anyhow::Error> {
+    let arg = |index: usize| args.get(index).cloned().unwrap_or(Kind::Any);
+    let element = |index: usize| get_array_element_type(&arg(index)).unwrap_or(Kind::Any);
+    let array = || kind!(Arr Kind::Any);
+    let numbers = || kind!(Arr Kind::Number);
+    let optional_datetime = || kind!(Opt(Kind::Datetime));
+    // in a `GROUP BY` select, aggregate functions are passed the value from each record
+    // of the group rather than an array, eg: `math::sum(score)` where `score` is an `int`
+    let aggregate = |index: usize| get_array_element_type(&arg(index)).unwrap_or(arg(index));
+    // functions that return `NONE` for an empty array always have a value in a group
+    let aggregate_result = |index: usize, kind: Kind| match get_array_element_type(&arg(index)) {
+        Ok(_) => optional(kind),
+        Err(_) => kind,
+    };
+
+    Ok(match name {
+        "count" => sig(vec![Kind::Any], Kind::Int),
+        "not" => sig(vec![Kind::Any], Kind::Bool),
+        "sleep" => sig(vec![Kind::Duration], Kind::Null),
+
+        // `array::` functions
+        "array::add" | "array::append" | "array::push" | "array::prepend" => sig(
+            vec![array(), Kind::Any],
+            kind!(Arr union(vec![element(0), arg(1)])),
+        ),
+        "array::insert" => sig(
+            vec![array(), Kind::Any, kind!(Opt(Kind::Int))],
+            kind!(Arr union(vec![element(0), arg(1)])),
+        ),
+        // filling a range of the array only replaces some of its elements
+        "array::fill" => sig(
+            vec![
+                array(),
+                Kind::Any,
+                kind!(Opt(Kind::Int)),
+                kind!(Opt(Kind::Int)),
+            ],
+            match args.len() {
+                0..=2 => kind!(Arr arg(1)),
+                _ => kind!(Arr union(vec![element(0), arg(1)])),
+            },
+        ),
+        "array::all" | "array::any" | "array::every" | "array::some" | "array::includes" => {
+            sig(vec![array(), Kind::Any], Kind::Bool)
+        }
+        "array::is_empty" => sig(vec![array()], Kind::Bool),
+        "array::at" => sig(vec![array(), Kind::Int], optional(element(0))),
+        "array::first" | "array::last" | "array::pop" | "array::max" | "array::min" => {
+            sig(vec![array()], optional(element(0)))
+        }
+        "array::find" => sig(vec![array(), Kind::Any], optional(element(0))),
+        "array::find_index" | "array::index_of" => {
+            sig(vec![array(), Kind::Any], kind!(Opt(Kind::Int)))
+        }
+        "array::filter_index" => sig(vec![array(), Kind::Any], kind!(Arr Kind::Int)),
+        "array::boolean_and" | "array::boolean_or" | "array::boolean_xor" => {
+            sig(vec![array(), array()], kind!(Arr Kind::Bool))
+        }
+        "array::boolean_not" => sig(vec![array()], kind!(Arr Kind::Bool)),
+        "array::matches" => sig(vec![array(), Kind::Any], kind!(Arr Kind::Bool)),
+        "array::clump" | "array::windows" => {
+            sig(vec![array(), Kind::Int], kind!(Arr kind!(Arr element(0))))
+        }
+        "array::combine" => sig(
+            vec![array(), array()],
+            kind!(Arr kind!(Arr union(vec![element(0), element(1)]))),
+        ),
+        "array::concat" | "array::union" | "array::difference" | "array::logical_and"
+        | "array::logical_or" | "array::logical_xor" => variadic(
+            vec![array(), array()],
+            array(),
+            kind!(Arr union((0..args.len()).map(element).collect())),
+        ),
+        "array::complement" | "array::intersect" => {
+            sig(vec![array(), array()], kind!(Arr element(0)))
+        }
+        "array::distinct" | "array::reverse" | "array::shuffle" => {
+            sig(vec![array()], kind!(Arr element(0)))
+        }
+        "array::filter" => sig(vec![array(), Kind::Any], kind!(Arr element(0))),
+        "array::remove" => sig(vec![array(), Kind::Int], kind!(Arr element(0))),
+        "array::slice" => sig(
+            vec![array(), kind!(Opt(Kind::Int)), kind!(Opt(Kind::Int))],
+            kind!(Arr element(0)),
+        ),
+        "array::sort" => sig(vec![array(), Kind::Any], kind!(Arr element(0))),
+        "array::sort::asc" | "array::sort::desc" => sig(vec![array()], kind!(Arr element(0))),
+        "array::swap" => sig(vec![array(), Kind::Int, Kind::Int], kind!(Arr element(0))),
+        "array::flatten" | "array::group" => sig(vec![array()], kind!(Arr flatten(&element(0)))),
+        "array::transpose" => sig(vec![array()], kind!(Arr kind!(Arr flatten(&element(0))))),
+        "array::join" => sig(vec![array(), Kind::String], Kind::String),
+        "array::len" => sig(vec![array()], Kind::Int),
+        "array::map" => sig(vec![array(), Kind::Any], array()),
+        "array::fold" | "array::reduce" => sig(vec![array(), Kind::Any, Kind::Any], Kind::Any),
+        "array::range" => sig(vec![Kind::Int, Kind::Int], kind!(Arr Kind::Int)),
+        "array::repeat" => sig(vec![Kind::Any, Kind::Int], kind!(Arr arg(0))),
+
+        // `bytes::` functions
+        "bytes::len" => sig(vec![Kind::Bytes], Kind::Int),
+
+        // `crypto::` functions
+        "crypto::blake3" | "crypto::md5" | "crypto::sha1" | "crypto::sha256" | "crypto::sha512" => {
+            sig(vec![Kind::String], Kind::String)
+        }
+        "crypto::argon2::compare"
+        | "crypto::bcrypt::compare"
+        | "crypto::pbkdf2::compare"
+        | "crypto::scrypt::compare" => sig(vec![Kind::String, Kind::String], Kind::Bool),
+        "crypto::argon2::generate"
+        | "crypto::bcrypt::generate"
+        | "crypto::pbkdf2::generate"
+        | "crypto::scrypt::generate" => sig(vec![Kind::String], Kind::String),
+
+        // `duration::` functions
+        "duration::days" | "duration::hours" | "duration::micros" | "duration::millis"
+        | "duration::mins" | "duration::nanos" | "duration::secs" | "duration::weeks"
+        | "duration::years" => sig(vec![Kind::Duration], Kind::Number),
+        "duration::from::days"
+        | "duration::from::hours"
+        | "duration::from::micros"
+        | "duration::from::millis"
+        | "duration::from::mins"
+        | "duration::from::nanos"
+        | "duration::from::secs"
+        | "duration::from::weeks" => sig(vec![Kind::Number], Kind::Duration),
+
+        // `encoding::` functions
+        "encoding::base64::decode" => sig(vec![Kind::String], Kind::Bytes),
+        "encoding::base64::encode" => sig(vec![Kind::Bytes], Kind::String),
+
+        // `geo::` functions
+        "geo::area" => sig(vec![Kind::Geometry(vec![])], Kind::Float),
+        "geo::bearing" | "geo::distance" => sig(vec![Kind::Point, Kind::Point], Kind::Float),
+        "geo::centroid" => sig(vec![Kind::Geometry(vec![])], Kind::Point),
+        "geo::hash::decode" => sig(vec![Kind::String], Kind::Point),
+        "geo::hash::encode" => sig(vec![Kind::Point, kind!(Opt(Kind::Int))], Kind::String),
+        "geo::is::valid" => sig(vec![Kind::Geometry(vec![])], Kind::Bool),
+
+        // `http::` functions return whatever the remote server responds with
+        "http::head" => sig(vec![Kind::String, kind!(Opt(Kind::Object))], Kind::Null),
+        "http::get" | "http::delete" => {
+            sig(vec![Kind::String, kind!(Opt(Kind::Object))], Kind::Any)
+        }
+        "http::patch" | "http::post" | "http::put" => sig(
+            vec![Kind::String, Kind::Any, kind!(Opt(Kind::Object))],
+            Kind::Any,
+        ),
+
+        // `math::` functions
+        "math::bottom" | "math::top" => sig(vec![numbers(), Kind::Int], kind!(Arr aggregate(0))),
+        // the largest or smallest value keeps the type of the values being compared,
+        // eg: `math::max(array<datetime>) -> option<datetime>`
+        "math::max" | "math::min" => sig(vec![numbers()], aggregate_result(0, aggregate(0))),
+        "math::sum" | "math::product" | "math::mode" | "math::nearestrank" | "math::spread" => {
+            sig(vec![numbers()], get_number_kind(&aggregate(0)))
+        }
+        "math::median" => sig(vec![numbers()], aggregate_result(0, Kind::Float)),
+        "math::interquartile"
+        | "math::mean"
+        | "math::midhinge"
+        | "math::stddev"
+        | "math::trimean"
+        | "math::variance" => sig(vec![numbers()], Kind::Float),
+        "math::percentile" => sig(vec![numbers(), Kind::Number], Kind::Float),
+        "math::abs" | "math::ceil" | "math::floor" | "math::round" | "math::sign" => {
+            sig(vec![Kind::Number], get_number_kind(&arg(0)))
+        }
+        "math::sqrt" => sig(
+            vec![Kind::Number],
+            match get_number_kind(&arg(0)) {
+                Kind::Decimal => Kind::Decimal,
+                _ => Kind::Float,
+            },
+        ),
+        "math::acos" | "math::acot" | "math::asin" | "math::atan" | "math::cos" | "math::cot"
+        | "math::deg2rad" | "math::ln" | "math::log10" | "math::log2" | "math::rad2deg"
+        | "math::sin" | "math::tan" => sig(vec![Kind::Number], Kind::Float),
+        "math::log" => sig(vec![Kind::Number, Kind::Number], Kind::Float),
+        "math::fixed" => sig(vec![Kind::Number, Kind::Int], Kind::Float),
+        "math::pow" => sig(
+            vec![Kind::Number, Kind::Number],
+            match (get_number_kind(&arg(0)), get_number_kind(&arg(1))) {
+                (Kind::Int, Kind::Int) => Kind::Int,
+                (Kind::Float, Kind::Float) => Kind::Float,
+                _ => Kind::Number,
+            },
+        ),
+        "math::clamp" => sig(
+            vec![Kind::Number, Kind::Number, Kind::Number],
+            match (
+                get_number_kind(&arg(0)),
+                get_number_kind(&arg(1)),
+                get_number_kind(&arg(2)),
+            ) {
+                (Kind::Int, Kind::Int, Kind::Int) => Kind::Int,
+                (Kind::Decimal, ..) => Kind::Decimal,
+                (Kind::Int | Kind::Float, ..) => Kind::Float,
+                _ => Kind::Number,
+            },
+        ),
+        "math::lerp" | "math::lerpangle" => sig(
+            vec![Kind::Number, Kind::Number, Kind::Number],
+            match get_number_kind(&arg(2)) {
+                Kind::Decimal => Kind::Decimal,
+                Kind::Int | Kind::Float => Kind::Float,
+                _ => Kind::Number,
+            },
+        ),
+        "math::e" | "math::inf" | "math::neg_inf" | "math::pi" | "math::tau" => {
+            sig(vec![], Kind::Float)
+        }
+
+        // `meta::` functions are aliases of `record::` functions
+        "meta::id" | "record::id" => sig(vec![Kind::Record(vec![])], Kind::Any),
+        "meta::tb" | "record::tb" | "record::table" => {
+            sig(vec![Kind::Record(vec![])], Kind::String)
+        }
+        "meta::type" => sig(vec![Kind::Any], Kind::String),
+        "record::exists" => sig(vec![Kind::Record(vec![])], Kind::Bool),
+
+        // `object::` functions
+        "object::entries" => sig(
+            vec![Kind::Object],
+            kind!(Arr Kind::Literal(Literal::Array(vec![
+                object_keys(&arg(0), state),
+                object_values(&arg(0), state),
+            ]))),
+        ),
+        "object::from_entries" => sig(vec![array()], Kind::Object),
+        "object::keys" => sig(vec![Kind::Object], kind!(Arr object_keys(&arg(0), state))),
+        "object::len" => sig(vec![Kind::Object], Kind::Int),
+        "object::values" => sig(vec![Kind::Object], kind!(Arr object_values(&arg(0), state))),
+
+        // `parse::` functions return `NONE` when the value can't be parsed
+        "parse::email::host"
+        | "parse::email::user"
+        | "parse::url::domain"
+        | "parse::url::fragment"
+        | "parse::url::host"
+        | "parse::url::path"
+        | "parse::url::query"
+        | "parse::url::scheme" => sig(vec![Kind::String], kind!(Opt(Kind::String))),
+        "parse::url::port" => sig(vec![Kind::String], kind!(Opt(Kind::Int))),
+
+        // `rand::` functions
+        "rand" => sig(vec![], Kind::Float),
+        "rand::float" => sig(
+            vec![kind!(Opt(Kind::Number)), kind!(Opt(Kind::Number))],
+            Kind::Float,
+        ),
+        "rand::bool" => sig(vec![], Kind::Bool),
+        "rand::enum" => sig(
+            vec![],
+            match args {
+                [kind @ (Kind::Array(..) | Kind::Literal(Literal::Array(_)))] => {
+                    get_array_element_type(kind)?
+                }
+                args => union(args.to_vec()),
+            },
+        ),
+        "rand::guid" | "rand::string" | "rand::ulid" => sig(vec![], Kind::String),
+        "rand::int" => sig(
+            vec![kind!(Opt(Kind::Int)), kind!(Opt(Kind::Int))],
+            Kind::Int,
+        ),
+        "rand::time" => sig(vec![], Kind::Datetime),
+        "rand::uuid" | "rand::uuid::v4" | "rand::uuid::v7" => sig(vec![], Kind::Uuid),
+
+        // `search::` functions
+        "search::analyze" => sig(vec![Kind::String, Kind::String], kind!(Arr Kind::String)),
+        "search::highlight" => sig(vec![Kind::String, Kind::String, Kind::Number], Kind::Any),
+        "search::offsets" => sig(vec![Kind::Number], Kind::Object),
+        "search::score" => sig(vec![Kind::Number], Kind::Float),
+
+        // `session::` functions
+        "session::ac" | "session::db" | "session::id" | "session::ip" | "session::ns"
+        | "session::origin" => sig(vec![], kind!(Opt(Kind::String))),
+        "session::rd" => sig(vec![], kind!(Opt(Kind::Record(vec![])))),
+        "session::token" => sig(vec![], kind!(Opt(Kind::Object))),
+
+        // `string::` functions
+        // any values can be concatenated or joined, and are converted to strings
+        "string::concat" => variadic(vec![], Kind::Any, Kind::String),
+        "string::contains" | "string::ends_with" | "string::starts_with" | "string::matches" => {
+            sig(vec![Kind::String, Kind::String], Kind::Bool)
+        }
+        "string::distance::damerau_levenshtein"
+        | "string::distance::hamming"
+        | "string::distance::levenshtein"
+        | "string::distance::osa_distance"
+        | "string::similarity::fuzzy" => sig(vec![Kind::String, Kind::String], Kind::Int),
+        "string::distance::normalized_damerau_levenshtein"
+        | "string::distance::normalized_levenshtein"
+        | "string::similarity::jaro"
+        | "string::similarity::jaro_winkler"
+        | "string::similarity::smithwaterman"
+        | "string::similarity::sorensen_dice" => sig(vec![Kind::String, Kind::String], Kind::Float),
+        "string::html::encode"
+        | "string::html::sanitize"
+        | "string::lowercase"
+        | "string::reverse"
+        | "string::slug"
+        | "string::trim"
+        | "string::uppercase" => sig(vec![Kind::String], Kind::String),
+        "string::is::alpha"
+        | "string::is::alphanum"
+        | "string::is::ascii"
+        | "string::is::domain"
+        | "string::is::email"
+        | "string::is::hexadecimal"
+        | "string::is::ip"
+        | "string::is::ipv4"
+        | "string::is::ipv6"
+        | "string::is::latitude"
+        | "string::is::longitude"
+        | "string::is::numeric"
+        | "string::is::semver"
+        | "string::is::ulid"
+        | "string::is::url"
+        | "string::is::uuid" => sig(vec![Kind::String], Kind::Bool),
+        "string::is::datetime" => sig(vec![Kind::String, kind!(Opt(Kind::String))], Kind::Bool),
+        "string::is::record" => sig(vec![Kind::String, kind!(Opt(Kind::String))], Kind::Bool),
+        "string::join" => variadic(vec![Kind::String], Kind::Any, Kind::String),
+        "string::len" => sig(vec![Kind::String], Kind::Int),
+        "string::repeat" => sig(vec![Kind::String, Kind::Int], Kind::String),
+        "string::replace" => sig(vec![Kind::String, Kind::String, Kind::String], Kind::String),
+        "string::semver::compare" => sig(vec![Kind::String, Kind::String], Kind::Int),
+        "string::semver::major" | "string::semver::minor" | "string::semver::patch" => {
+            sig(vec![Kind::String], Kind::Int)
+        }
+        "string::semver::inc::major"
+        | "string::semver::inc::minor"
+        | "string::semver::inc::patch" => sig(vec![Kind::String], Kind::String),
+        "string::semver::set::major"
+        | "string::semver::set::minor"
+        | "string::semver::set::patch" => sig(vec![Kind::String, Kind::Int], Kind::String),
+        "string::slice" => sig(
+            vec![Kind::String, kind!(Opt(Kind::Int)), kind!(Opt(Kind::Int))],
+            Kind::String,
+        ),
+        "string::split" => sig(vec![Kind::String, Kind::String], kind!(Arr Kind::String)),
+        "string::words" => sig(vec![Kind::String], kind!(Arr Kind::String)),
+
+        // `time::` functions
+        "time::ceil" | "time::floor" | "time::round" => {
+            sig(vec![Kind::Datetime, Kind::Duration], Kind::Datetime)
+        }
+        "time::day" | "time::hour" | "time::micros" | "time::millis" | "time::minute"
+        | "time::month" | "time::nano" | "time::second" | "time::unix" | "time::wday"
+        | "time::week" | "time::yday" | "time::year" => sig(vec![optional_datetime()], Kind::Int),
+        "time::format" => sig(vec![Kind::Datetime, Kind::String], Kind::String),
+        "time::group" => sig(vec![Kind::Datetime, Kind::String], Kind::Datetime),
+        "time::is::leap_year" => sig(vec![optional_datetime()], Kind::Bool),
+        "time::max" | "time::min" => sig(vec![kind!(Arr Kind::Datetime)], Kind::Datetime),
+        "time::now" => sig(vec![], Kind::Datetime),
+        "time::timezone" => sig(vec![], Kind::String),
+        "time::from::micros" | "time::from::millis" | "time::from::nanos" | "time::from::secs"
+        | "time::from::unix" => sig(vec![Kind::Number], Kind::Datetime),
+        "time::from::ulid" => sig(vec![Kind::String], Kind::Datetime),
+        "time::from::uuid" => sig(vec![Kind::Uuid], Kind::Datetime),
+
+        // `type::` functions
+        "type::array" => sig(
+            vec![Kind::Any],
+            match arg(0) {
+                kind @ (Kind::Array(..) | Kind::Literal(Literal::Array(_))) => kind,
+                _ => array(),
+            },
+        ),
+        "type::bool" => sig(vec![Kind::Any], Kind::Bool),
+        "type::bytes" => sig(vec![Kind::Any], Kind::Bytes),
+        "type::datetime" => sig(vec![Kind::Any], Kind::Datetime),
+        "type::decimal" => sig(vec![Kind::Any], Kind::Decimal),
+        "type::duration" => sig(vec![Kind::Any], Kind::Duration),
+        "type::field" => sig(vec![Kind::String], Kind::Any),
+        "type::fields" => sig(vec![kind!(Arr Kind::String)], array()),
+        "type::float" => sig(vec![Kind::Any], Kind::Float),
+        "type::geometry" => sig(vec![Kind::Any], Kind::Geometry(vec![])),
+        "type::int" => sig(vec![Kind::Any], Kind::Int),
+        "type::number" => sig(vec![Kind::Any], Kind::Number),
+        "type::point" => sig(vec![Kind::Any], Kind::Point),
+        "type::range" => sig(vec![Kind::Any], Kind::Any),
+        "type::record" | "type::thing" => sig(
+            vec![Kind::Any],
+            match (values.first(), arg(0)) {
+                // `type::thing("user", $id)` is a link to the `user` table
+                (Some(Value::Strand(table)), _) => Kind::Record(vec![table.as_str().into()]),
+                (Some(Value::Table(table)), _) => Kind::Record(vec![table.clone()]),
+                (_, kind @ Kind::Record(_)) if values.len() == 1 => kind,
+                _ => Kind::Record(vec![]),
+            },
+        ),
+        "type::string" => sig(vec![Kind::Any], Kind::String),
+        "type::table" => sig(vec![Kind::Any], Kind::String),
+        "type::uuid" => sig(vec![Kind::Any], Kind::Uuid),
+        name if name.starts_with("type::is::") => sig(vec![Kind::Any], Kind::Bool),
+
+        // `value::` functions
+        "value::diff" => sig(vec![Kind::Any, Kind::Any], kind!(Arr Kind::Object)),
+        "value::patch" => sig(vec![Kind::Any, array()], arg(0)),
+
+        // `vector::` functions
+        "vector::add" | "vector::cross" | "vector::divide" | "vector::multiply"
+        | "vector::project" | "vector::subtract" => sig(vec![numbers(), numbers()], numbers()),
+        "vector::normalize" => sig(vec![numbers()], numbers()),
+        "vector::scale" => sig(vec![numbers(), Kind::Number], numbers()),
+        "vector::magnitude" => sig(vec![numbers()], Kind::Number),
+        "vector::angle"
+        | "vector::dot"
+        | "vector::distance::chebyshev"
+        | "vector::distance::euclidean"
+        | "vector::distance::hamming"
+        | "vector::distance::mahalanobis"
+        | "vector::distance::manhattan"
+        | "vector::similarity::cosine"
+        | "vector::similarity::jaccard"
+        | "vector::similarity::pearson"
+        | "vector::similarity::spearman" => sig(vec![numbers(), numbers()], Kind::Number),
+        "vector::distance::minkowski" => {
+            sig(vec![numbers(), numbers(), Kind::Number], Kind::Number)
+        }
+        "vector::distance::knn" => sig(vec![], Kind::Number),
+
+        _ => anyhow::bail!("Unsupported normal function: {}", name),
+    })
+}
+
+/// Get the type an aggregate function is stored as in an aggregate view, eg:
+/// `DEFINE TABLE ... AS SELECT math::mean(score) ... GROUP BY ...`.
+///
+/// Views don't call aggregate functions, they update running values as records change,
+/// and `math::mean` keeps its running mean as a `decimal` rather than the `float` it returns.
+pub fn get_view_aggregate_return_type(name: &str, return_type: Kind) -> Kind {
+    match name {
+        "math::mean" => Kind::Decimal,
+        _ => return_type,
+    }
+}
+
+/// A union of kinds, which is `any` if any of the kinds are `any`
+fn union(kinds: Vec<Kind>) -> Kind {
+    if kinds.is_empty() || kinds.contains(&Kind::Any) {
+        return Kind::Any;
+    }
+
+    union_kinds(kinds)
+}
+
+/// The element type of an array after it has been flattened by one level
+fn flatten(kind: &Kind) -> Kind {
+    match kind {
+        Kind::Either(kinds) => union(kinds.iter().map(flatten).collect()),
+        Kind::Array(..) | Kind::Set(..) | Kind::Literal(Literal::Array(_)) => {
+            get_array_element_type(kind).unwrap_or(Kind::Any)
+        }
+        kind => kind.clone(),
+    }
+}
+
+/// The keys of an object, as a union of string literals if they are known
+fn object_keys(kind: &Kind, state: &QueryState) -> Kind {
+    match kind {
+        Kind::Option(inner) => object_keys(inner, state),
+        Kind::Literal(Literal::Object(fields))
+            if !fields.is_empty() && !state.schema.schema.is_schemaless_record(fields) =>
+        {
+            union_kinds(
+                fields
+                    .keys()
+                    .map(|key| Kind::Literal(Literal::String(key.clone().into())))
+                    .collect(),
+            )
+        }
+        _ => Kind::String,
+    }
+}
+
+fn object_values(kind: &Kind, state: &QueryState) -> Kind {
+    match kind {
+        Kind::Option(inner) => object_values(inner, state),
+        Kind::Literal(Literal::Object(fields))
+            if !state.schema.schema.is_schemaless_record(fields) =>
+        {
+            union(fields.values().cloned().collect())
+        }
+        _ => Kind::Any,
+    }
+}
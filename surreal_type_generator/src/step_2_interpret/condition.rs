@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+
+use surrealdb::sql::{Cond, Expression, Literal, Operator, Param, Subquery, Value};
+
+use crate::{kind, Kind};
+
+use super::{
+    return_types::get_value_return_type,
+    schema::QueryState,
+    utils::{get_array_element_type, get_what_fields, widen_literal},
+};
+
+/// Which side of a comparison an untyped parameter is on
+#[derive(Clone, Copy)]
+enum Side {
+    Left,
+    Right,
+}
+
+/// Infer the types of any untyped parameters that are compared against fields
+/// in a `WHERE` clause, eg: `WHERE email = $email` or `WHERE $tag IN tags`.
+pub fn infer_cond_params(
+    what: &[Value],
+    cond: &Cond,
+    state: &mut QueryState,
+) -> Result<(), anyhow::Error> {
+    for table in what.iter() {
+        let fields = get_what_fields(table, state)?;
+
+        state.push_stack_frame();
+        state.set_local("this", kind!(Obj fields.clone()));
+
+        let result = infer_value_params(&cond.0, &fields, state);
+
+        state.pop_stack_frame();
+
+        result?;
+    }
+
+    Ok(())
+}
+
+/// Check the condition of an array filter, eg: `addresses[WHERE city = $city]`, which is
+/// evaluated against each element of the array, with the element bound as `$this`.
+pub fn check_filter_cond(
+    cond: &Value,
+    element: &Kind,
+    state: &mut QueryState,
+) -> Result<(), anyhow::Error> {
+    for fields in get_element_fields(element, state)? {
+        state.push_stack_frame();
+        state.set_local("this", element.clone());
+
+        let result = infer_value_params(cond, &fields, state)
+            .and_then(|_| check_cond_operands(cond, &fields, state));
+
+        state.pop_stack_frame();
+
+        result?;
+    }
+
+    Ok(())
+}
+
+/// Type every operand of a condition, so that unknown fields and invalid expressions are
+/// reported, as comparisons are typed as a `bool` without looking at what is being compared
+fn check_cond_operands(
+    value: &Value,
+    fields: &BTreeMap<String, Kind>,
+    state: &mut QueryState,
+) -> Result<(), anyhow::Error> {
+    match value {
+        Value::Expression(expr) => match expr.as_ref() {
+            Expression::Binary { l, r, .. } => {
+                check_cond_operands(l, fields, state)?;
+                check_cond_operands(r, fields, state)
+            }
+            Expression::Unary { v, .. } => check_cond_operands(v, fields, state),
+            #[allow(unreachable_patterns)]
+            _ => Ok(()),
+        },
+        Value::Subquery(subquery) => match subquery.as_ref() {
+            Subquery::Value(value) => check_cond_operands(value, fields, state),
+            _ => get_value_return_type(value, fields, state).map(|_| ()),
+        },
+        value => get_value_return_type(value, fields, state).map(|_| ()),
+    }
+}
+
+/// Get the fields that can be read from an array element, with one set of fields for each
+/// table the element could be a record of
+fn get_element_fields(
+    element: &Kind,
+    state: &mut QueryState,
+) -> Result<Vec<BTreeMap<String, Kind>>, anyhow::Error> {
+    Ok(match element {
+        Kind::Literal(Literal::Object(fields)) => vec![fields.clone()],
+        Kind::Record(tables) if !tables.is_empty() => {
+            let mut table_fields = Vec::new();
+            for table in tables {
+                table_fields.push(state.table_select_fields(table.as_str())?);
+            }
+            table_fields
+        }
+        Kind::Option(inner) => get_element_fields(inner, state)?,
+        Kind::Either(kinds) => {
+            let mut element_fields = Vec::new();
+            for kind in kinds {
+                element_fields.extend(get_element_fields(kind, state)?);
+            }
+            element_fields
+        }
+        // the fields of untyped objects and records are unknown, so there is nothing to check
+        Kind::Object | Kind::Record(_) | Kind::Any => vec![],
+        _ => vec![BTreeMap::new()],
+    })
+}
+
+fn infer_value_params(
+    value: &Value,
+    fields: &BTreeMap<String, Kind>,
+    state: &mut QueryState,
+) -> Result<(), anyhow::Error> {
+    match value {
+        Value::Expression(expr) => match expr.as_ref() {
+            Expression::Binary {
+                l,
+                o: Operator::And | Operator::Or,
+                r,
+            } => {
+                infer_value_params(l, fields, state)?;
+                infer_value_params(r, fields, state)
+            }
+            Expression::Binary { l, o, r } => {
+                let (param, other, side) =
+                    match (get_untyped_param(l, state), get_untyped_param(r, state)) {
+                        (None, Some(param)) => (param, l, Side::Right),
+                        (Some(param), None) => (param, r, Side::Left),
+                        _ => return Ok(()),
+                    };
+
+                let other_type = widen_literal(&get_value_return_type(other, fields, state)?);
+
+                match get_operand_type(o, side, &other_type)? {
+                    Some(param_type) => state.infer(param.as_str(), param_type),
+                    None => Ok(()),
+                }
+            }
+            Expression::Unary { v, .. } => infer_value_params(v, fields, state),
+            #[allow(unreachable_patterns)]
+            _ => Ok(()),
+        },
+        // parenthesized conditions, eg: `WHERE (a = $a OR b = $b) AND c = $c`
+        Value::Subquery(subquery) => match subquery.as_ref() {
+            Subquery::Value(value) => infer_value_params(value, fields, state),
+            _ => Ok(()),
+        },
+        _ => Ok(()),
+    }
+}
+
+fn get_untyped_param<'a>(value: &'a Value, state: &QueryState) -> Option<&'a Param> {
+    match value {
+        Value::Param(param) if state.is_untyped(param.as_str()) => Some(param),
+        _ => None,
+    }
+}
+
+/// Get the type of a parameter from the type of the value it's being compared against
+fn get_operand_type(
+    operator: &Operator,
+    side: Side,
+    other: &Kind,
+) -> Result<Option<Kind>, anyhow::Error> {
+    Ok(Some(match (operator, side) {
+        (
+            Operator::Equal
+            | Operator::Exact
+            | Operator::NotEqual
+            | Operator::Like
+            | Operator::NotLike
+            | Operator::LessThan
+            | Operator::LessThanOrEqual
+            | Operator::MoreThan
+            | Operator::MoreThanOrEqual,
+            _,
+        ) => other.clone(),
+        // `tags CONTAINS $tag`, `$tag IN tags`, `tags ?= $tag`
+        (
+            Operator::Contain | Operator::NotContain | Operator::AllEqual | Operator::AnyEqual,
+            Side::Right,
+        )
+        | (Operator::Inside | Operator::NotInside, Side::Left) => get_element_type(other)?,
+        // `$tags CONTAINS tag`, `tag IN $tags`, `$tags ?= tag`
+        (
+            Operator::Contain | Operator::NotContain | Operator::AllEqual | Operator::AnyEqual,
+            Side::Left,
+        )
+        | (Operator::Inside | Operator::NotInside, Side::Right) => kind!(Arr other.clone()),
+        // `tags CONTAINSANY $tags`, `$tags ALLINSIDE tags`
+        (
+            Operator::ContainAll
+            | Operator::ContainAny
+            | Operator::ContainNone
+            | Operator::AllInside
+            | Operator::AnyInside
+            | Operator::NoneInside,
+            _,
+        ) => kind!(Arr get_element_type(other)?),
+        _ => return Ok(None),
+    }))
+}
+
+fn get_element_type(kind: &Kind) -> Result<Kind, anyhow::Error> {
+    match kind {
+        // `CONTAINS` also checks for substrings
+        Kind::String => Ok(Kind::String),
+        kind => get_array_element_type(kind),
+    }
+}
@@ -0,0 +1,370 @@
+use std::collections::BTreeMap;
+
+use surrealdb::sql::{Idiom, Literal, Object, Operator, Param, Part, Value};
+
+use crate::{
+    kind,
+    step_1_parse_sql::{RecordChange, TableParsed},
+    Kind, PrettyString,
+};
+
+use super::{
+    return_types::get_value_return_type,
+    schema::{QueryState, SchemaState},
+    utils::{get_array_element_type, get_value_table, is_assignable, union_kinds},
+};
+
+/// Computes the fields that can be written to by a statement, eg: [`TableParsed::compute_create_fields`]
+pub type ComputeFields = fn(&TableParsed) -> anyhow::Result<BTreeMap<String, Kind>>;
+
+/// Infer the types of any parameters in a `SET` clause from the fields they are assigned to,
+/// and check any literal values against the field types of every table being written to.
+pub fn validate_set_expressions(
+    what: &[Value],
+    sets: &[(Idiom, Operator, Value)],
+    compute_fields: ComputeFields,
+    state: &mut QueryState,
+) -> Result<(), anyhow::Error> {
+    let schema = state.schema.clone();
+
+    for table in what.iter() {
+        let table = get_data_table(table, &schema, state)?;
+        let create_fields = compute_fields(table)?;
+        let select_fields = table.compute_select_fields()?;
+
+        for (idiom, operator, value) in sets.iter() {
+            let field_type = match get_idiom_type(idiom, &create_fields)? {
+                Some(field_type) => field_type,
+                None if is_readonly_field(idiom, table) => {
+                    anyhow::bail!("Field `{}` is readonly on table `{}`", idiom, table.name)
+                }
+                // fields with a `VALUE` clause are computed by the database, so setting them is a no-op
+                None if get_idiom_type(idiom, &select_fields)?.is_some() => continue,
+                // schemaless tables accept fields that aren't defined in the schema
+                None if !table.schemafull => Kind::Any,
+                None => anyhow::bail!("Field `{}` does not exist on table `{}`", idiom, table.name),
+            };
+
+            match operator {
+                Operator::Equal => {
+                    let select_type = get_idiom_type(idiom, &select_fields)?;
+                    validate_value(
+                        idiom,
+                        value,
+                        &field_type,
+                        select_type.as_ref(),
+                        table,
+                        state,
+                    )?
+                }
+                // `tags += $tag` appends to arrays, otherwise it adds to or subtracts from the field
+                Operator::Inc | Operator::Dec | Operator::Ext => match &field_type {
+                    Kind::Array(element_type, ..) | Kind::Set(element_type, ..) => {
+                        let element_type = *element_type.clone();
+
+                        match value {
+                            Value::Param(param) if state.is_untyped(param.as_str()) => {
+                                state.infer(param.as_str(), element_type)?
+                            }
+                            value => check_value(
+                                idiom,
+                                value,
+                                &field_type,
+                                &union_kinds(vec![element_type.clone(), kind!(Arr element_type)]),
+                                state,
+                            )?,
+                        }
+                    }
+                    _ => validate_value(idiom, value, &field_type, None, table, state)?,
+                },
+                _ => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Infer the types of any parameters nested inside of an object literal, such as
+/// `CONTENT { name: $name }` or `MERGE { address: { city: $city } }`, and check any
+/// literal values against the field types of every table being written to.
+pub fn validate_object_content(
+    what: &[Value],
+    object: &Object,
+    compute_fields: ComputeFields,
+    state: &mut QueryState,
+) -> Result<(), anyhow::Error> {
+    let schema = state.schema.clone();
+
+    for table in what.iter() {
+        let table = get_data_table(table, &schema, state)?;
+        let create_fields = compute_fields(table)?;
+        let select_fields = table.compute_select_fields()?;
+
+        validate_object(
+            &Idiom::default(),
+            object,
+            &create_fields,
+            &select_fields,
+            table,
+            state,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Infer the type of a `PATCH $ops` parameter as an array of the JSON Patch
+/// operations that can be applied to every table being written to
+pub fn infer_patch_param(
+    what: &[Value],
+    param: &Param,
+    state: &mut QueryState,
+) -> Result<(), anyhow::Error> {
+    let schema = state.schema.clone();
+    let mut operations = Vec::new();
+
+    for table in what.iter() {
+        let table = get_data_table(table, &schema, state)?;
+
+        for operation in table.compute_patch_operations()? {
+            if !operations.contains(&operation) {
+                operations.push(operation);
+            }
+        }
+    }
+
+    match operations.len() {
+        0 => Ok(()),
+        1 => state.infer(param.as_str(), kind!(Arr operations.pop().unwrap())),
+        _ => state.infer(param.as_str(), kind!(Arr Kind::Either(operations))),
+    }
+}
+
+/// Get the type of the JSON Patch operations returned by `RETURN DIFF`
+pub fn get_diff_type(
+    what: &[Value],
+    change: RecordChange,
+    state: &mut QueryState,
+) -> Result<Kind, anyhow::Error> {
+    let schema = state.schema.clone();
+    let mut operations = Vec::new();
+
+    for table in what.iter() {
+        let table = get_data_table(table, &schema, state)?;
+        operations.extend(table.compute_diff_operations(change)?);
+    }
+
+    Ok(union_kinds(operations))
+}
+
+fn get_data_table<'a>(
+    what: &Value,
+    schema: &'a SchemaState,
+    state: &mut QueryState,
+) -> Result<&'a TableParsed, anyhow::Error> {
+    let table_name = get_value_table(what, state)?;
+
+    match schema.schema.tables.get(&table_name) {
+        Some(table) => Ok(table),
+        None => anyhow::bail!(
+            "Trying to write to an unknown or view table: {}",
+            table_name
+        ),
+    }
+}
+
+fn validate_object(
+    path: &Idiom,
+    object: &Object,
+    create_fields: &BTreeMap<String, Kind>,
+    select_fields: &BTreeMap<String, Kind>,
+    table: &TableParsed,
+    state: &mut QueryState,
+) -> Result<(), anyhow::Error> {
+    for (key, value) in object.iter() {
+        let key_path = join_idiom(path, Part::Field(key.as_str().into()));
+
+        match create_fields.get(key) {
+            Some(field_type) => validate_value(
+                &key_path,
+                value,
+                field_type,
+                select_fields.get(key),
+                table,
+                state,
+            )?,
+            None if is_readonly_field(&key_path, table) => {
+                anyhow::bail!("Field `{}` is readonly on table `{}`", key_path, table.name)
+            }
+            // fields with a `VALUE` clause are computed by the database
+            None if select_fields.contains_key(key) => {}
+            // schemaless tables accept fields that aren't defined in the schema
+            None if !table.schemafull => {
+                validate_value(&key_path, value, &Kind::Any, None, table, state)?
+            }
+            None => anyhow::bail!(
+                "Field `{}` does not exist on table `{}`",
+                key_path,
+                table.name
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+/// Infer or check a value that is being written to a field of type `field_type`
+fn validate_value(
+    path: &Idiom,
+    value: &Value,
+    field_type: &Kind,
+    select_type: Option<&Kind>,
+    table: &TableParsed,
+    state: &mut QueryState,
+) -> Result<(), anyhow::Error> {
+    match value {
+        Value::Param(param) if state.is_untyped(param.as_str()) => {
+            state.infer(param.as_str(), field_type.clone())
+        }
+        Value::Object(object) => match get_object_fields(field_type) {
+            Some(create_fields) => validate_object(
+                path,
+                object,
+                create_fields,
+                select_type
+                    .and_then(get_object_fields)
+                    .unwrap_or(create_fields),
+                table,
+                state,
+            ),
+            None => check_value(path, value, field_type, field_type, state),
+        },
+        Value::Array(array) if !is_literal_value(value) => {
+            let element_type = match get_array_element_type(field_type) {
+                Ok(element_type) => element_type,
+                Err(_) => anyhow::bail!(
+                    "Cannot set field `{}` of type `{}` to an array",
+                    path,
+                    field_type.pretty_string()
+                ),
+            };
+            let select_element_type =
+                select_type.and_then(|kind| get_array_element_type(kind).ok());
+            let element_path = join_idiom(path, Part::All);
+
+            for value in array.iter() {
+                validate_value(
+                    &element_path,
+                    value,
+                    &element_type,
+                    select_element_type.as_ref(),
+                    table,
+                    state,
+                )?;
+            }
+
+            Ok(())
+        }
+        value => check_value(path, value, field_type, field_type, state),
+    }
+}
+
+/// Whether the top level field of a path is `READONLY`, and so can't be updated
+fn is_readonly_field(path: &Idiom, table: &TableParsed) -> bool {
+    match path.first() {
+        Some(Part::Field(name)) => table
+            .fields
+            .get(name.as_str())
+            .is_some_and(|field| field.readonly),
+        _ => false,
+    }
+}
+
+fn join_idiom(path: &Idiom, part: Part) -> Idiom {
+    let mut parts = path.0.clone();
+    parts.push(part);
+    Idiom::from(parts)
+}
+
+fn get_object_fields(kind: &Kind) -> Option<&BTreeMap<String, Kind>> {
+    match kind {
+        Kind::Option(inner) => get_object_fields(inner),
+        Kind::Literal(Literal::Object(fields)) => Some(fields),
+        _ => None,
+    }
+}
+
+fn check_value(
+    path: &Idiom,
+    value: &Value,
+    field_type: &Kind,
+    expected_type: &Kind,
+    state: &mut QueryState,
+) -> Result<(), anyhow::Error> {
+    let actual_type = get_value_return_type(value, &BTreeMap::new(), state)?;
+
+    if !is_assignable(&actual_type, expected_type) {
+        anyhow::bail!(
+            "Cannot set field `{}` of type `{}` to `{}` of type `{}`",
+            path,
+            field_type.pretty_string(),
+            value,
+            actual_type.pretty_string()
+        );
+    }
+
+    Ok(())
+}
+
+fn is_literal_value(value: &Value) -> bool {
+    match value {
+        Value::None
+        | Value::Null
+        | Value::Bool(_)
+        | Value::Number(_)
+        | Value::Strand(_)
+        | Value::Duration(_)
+        | Value::Datetime(_)
+        | Value::Thing(_) => true,
+        Value::Array(array) => array.iter().all(is_literal_value),
+        Value::Object(object) => object.values().all(is_literal_value),
+        _ => false,
+    }
+}
+
+/// Get the type of a possibly nested field, such as `address.city` or `tags[0]`,
+/// or `None` if the field doesn't exist.
+pub fn get_idiom_type(
+    idiom: &Idiom,
+    fields: &BTreeMap<String, Kind>,
+) -> Result<Option<Kind>, anyhow::Error> {
+    let mut current = kind!(Obj fields.clone());
+
+    for part in idiom.iter() {
+        let parent = match current {
+            Kind::Option(inner) => *inner,
+            kind => kind,
+        };
+
+        current = match (part, parent) {
+            (_, Kind::Any | Kind::Object) => return Ok(Some(Kind::Any)),
+            (Part::Field(name), Kind::Literal(Literal::Object(fields))) => {
+                match fields.get(name.as_str()) {
+                    Some(kind) => kind.clone(),
+                    None => return Ok(None),
+                }
+            }
+            (Part::All | Part::Index(_) | Part::First | Part::Last, parent) => {
+                get_array_element_type(&parent)?
+            }
+            (_, parent) => anyhow::bail!(
+                "Unsupported path `{}` on a field of type `{}`",
+                idiom,
+                parent.pretty_string()
+            ),
+        };
+    }
+
+    Ok(Some(current))
+}
@@ -0,0 +1,114 @@
+use surrealdb::sql::{Fetchs, Idiom, Literal, Part, Table, Value};
+
+use crate::{kind, Kind, PrettyString};
+
+use super::{schema::QueryState, utils::union_kinds};
+
+/// Replace the record links at each `FETCH` path with the records they point to,
+/// eg: `FETCH author` turns `author: record<user>` into `author: { id: record<user>, ... }`
+pub fn get_fetch_type(
+    kind: Kind,
+    fetchs: &Fetchs,
+    state: &mut QueryState,
+) -> Result<Kind, anyhow::Error> {
+    let mut kind = kind;
+
+    for fetch in fetchs.iter() {
+        let idiom = match &fetch.0 {
+            Value::Idiom(idiom) => idiom,
+            value => anyhow::bail!("Unsupported FETCH clause: {}", value),
+        };
+
+        kind = get_fetch_path_type(&kind, idiom, idiom, state)?;
+    }
+
+    Ok(kind)
+}
+
+fn get_fetch_path_type(
+    kind: &Kind,
+    fetch: &Idiom,
+    parts: &[Part],
+    state: &mut QueryState,
+) -> Result<Kind, anyhow::Error> {
+    // arrays, options and unions are fetched through, at any point in the path
+    match kind {
+        Kind::Option(inner) => {
+            return Ok(kind!(Opt(get_fetch_path_type(inner, fetch, parts, state)?)))
+        }
+        Kind::Either(kinds) => {
+            let mut fetched = Vec::new();
+            for kind in kinds {
+                fetched.push(get_fetch_path_type(kind, fetch, parts, state)?);
+            }
+            return Ok(union_kinds(fetched));
+        }
+        Kind::Array(element, size) => {
+            let rest = match parts.first() {
+                Some(Part::All) => &parts[1..],
+                _ => parts,
+            };
+            let element = get_fetch_path_type(element, fetch, rest, state)?;
+            return Ok(Kind::Array(Box::new(element), *size));
+        }
+        Kind::Set(element, size) => {
+            let rest = match parts.first() {
+                Some(Part::All) => &parts[1..],
+                _ => parts,
+            };
+            let element = get_fetch_path_type(element, fetch, rest, state)?;
+            return Ok(Kind::Set(Box::new(element), *size));
+        }
+        Kind::Literal(Literal::Array(elements)) => {
+            let rest = match parts.first() {
+                Some(Part::All) => &parts[1..],
+                _ => parts,
+            };
+            let mut fetched = Vec::new();
+            for element in elements {
+                fetched.push(get_fetch_path_type(element, fetch, rest, state)?);
+            }
+            return Ok(Kind::Literal(Literal::Array(fetched)));
+        }
+        Kind::Any | Kind::Object => return Ok(Kind::Any),
+        _ => {}
+    }
+
+    match (parts.first(), kind) {
+        (None, Kind::Record(tables)) => get_record_type(tables, state),
+        // fetching anything other than a record link leaves it as it is
+        (None, kind) => Ok(kind.clone()),
+        (Some(Part::Field(name)), Kind::Literal(Literal::Object(fields))) => {
+            let mut fields = fields.clone();
+
+            // fields that don't exist are left as they are
+            if let Some(field) = fields.get(name.as_str()) {
+                let fetched = get_fetch_path_type(field, fetch, &parts[1..], state)?;
+                fields.insert(name.to_string(), fetched);
+            }
+
+            Ok(kind!(Obj fields))
+        }
+        // a record link part way through the path is fetched, but not the rest of the path
+        (Some(Part::Field(_)), Kind::Record(tables)) => get_record_type(tables, state),
+        (Some(_), kind) => anyhow::bail!(
+            "Unsupported FETCH path `{}` on a value of type `{}`",
+            fetch,
+            kind.pretty_string()
+        ),
+    }
+}
+
+fn get_record_type(tables: &[Table], state: &mut QueryState) -> Result<Kind, anyhow::Error> {
+    // a record link to any table could be any record
+    if tables.is_empty() {
+        return Ok(Kind::Any);
+    }
+
+    let mut records = Vec::new();
+    for table in tables {
+        records.push(kind!(Obj state.table_select_fields(&table.0)?));
+    }
+
+    Ok(union_kinds(records))
+}
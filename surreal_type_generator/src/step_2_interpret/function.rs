@@ -1,16 +1,27 @@
+use std::collections::BTreeMap;
+
 use surrealdb::sql::{Function, Value};
 
-use crate::Kind;
+use crate::{Kind, PrettyString};
 
-use super::QueryState;
+use super::{
+    builtin_function::get_builtin_function_signature,
+    return_types::get_value_return_type,
+    schema::InterpretedFunction,
+    utils::{is_assignable, widen_literal},
+    QueryState,
+};
 
 pub fn get_function_return_type(
     state: &mut QueryState,
     func: &Function,
+    field_types: &BTreeMap<String, Kind>,
 ) -> Result<Kind, anyhow::Error> {
     match func {
         Function::Custom(name, values) => get_custom_function_return_type(state, name, values),
-        Function::Normal(name, ..) => normal_function_return_type(name),
+        Function::Normal(name, values) => {
+            get_normal_function_return_type(state, name, values, field_types)
+        }
         Function::Script(..) => anyhow::bail!("Script functions are not yet supported"),
         _ => anyhow::bail!("Unsupported function: {}", func),
     }
@@ -19,145 +30,122 @@ pub fn get_function_return_type(
 pub fn get_custom_function_return_type(
     state: &mut QueryState,
     name: &str,
-    _values: &[Value],
+    values: &[Value],
 ) -> Result<Kind, anyhow::Error> {
     let function = state.function(name)?;
 
+    check_custom_function_arguments(state, &function, values)?;
+
     Ok(function.return_type)
 }
 
-pub fn normal_function_return_type(name: &str) -> Result<Kind, anyhow::Error> {
-    Ok(match name {
-        "count" => Kind::Number,
-
-        // `math::` functions
-        "math::abs" => Kind::Number,
-        "math::acos" => Kind::Number,
-        "math::asin" => Kind::Number,
-        "math::atan" => Kind::Number,
-        "math::bottom" => Kind::Array(Box::new(Kind::Number), None),
-        "math::ceil" => Kind::Number,
-        "math::clamp" => Kind::Number,
-        "math::cos" => Kind::Number,
-        "math::cot" => Kind::Number,
-        "math::deg2rad" => Kind::Number,
-        "math::e" => Kind::Number,
-        "math::fixed" => Kind::Number,
-        "math::floor" => Kind::Number,
-        "math::inf" => Kind::Number,
-        "math::interquartile" => Kind::Number,
-        "math::lerp" => Kind::Number,
-        "math::lerpangle" => Kind::Number,
-        "math::ln" => Kind::Number,
-        "math::log" => Kind::Number,
-        "math::log10" => Kind::Number,
-        "math::log2" => Kind::Number,
-        "math::max" => Kind::Number,
-        "math::mean" => Kind::Number,
-        "math::median" => Kind::Number,
-        "math::midhinge" => Kind::Number,
-        "math::min" => Kind::Number,
-        "math::mode" => Kind::Number,
-        "math::nearestrank" => Kind::Number,
-        "math::neg_inf" => Kind::Number,
-        "math::percentile" => Kind::Number,
-        "math::pi" => Kind::Number,
-        "math::product" => Kind::Number,
-        "math::rad2deg" => Kind::Number,
-        "math::round" => Kind::Number,
-        "math::sign" => Kind::Number,
-        "math::sin" => Kind::Number,
-        "math::tan" => Kind::Number,
-        "math::tau" => Kind::Number,
-        "math::spread" => Kind::Number,
-        "math::sqrt" => Kind::Number,
-        "math::stddev" => Kind::Number,
-        "math::sum" => Kind::Number,
-        "math::top" => Kind::Array(Box::new(Kind::Number), None),
-        "math::trimean" => Kind::Number,
-        "math::variance" => Kind::Number,
-
-        // `time::` functions
-        "time::day" => Kind::Number,
-        "time::floor" => Kind::Number,
-        "time::format" => Kind::String,
-        "time::group" => Kind::Datetime,
-        "time::hour" => Kind::Number,
-        "time::max" => Kind::Datetime,
-        "time::micros" => Kind::Number,
-        "time::millis" => Kind::Number,
-        "time::min" => Kind::Datetime,
-        "time::minute" => Kind::Number,
-        "time::month" => Kind::Number,
-        "time::nano" => Kind::Number,
-        "time::now" => Kind::Datetime,
-        "time::round" => Kind::Datetime,
-        "time::second" => Kind::Number,
-        "time::timezone" => Kind::String,
-        "time::unix" => Kind::Number,
-        "time::wday" => Kind::Number,
-        "time::week" => Kind::Number,
-        "time::yday" => Kind::Number,
-        "time::year" => Kind::Number,
-        "time::from::micros" => Kind::Datetime,
-        "time::from::millis" => Kind::Datetime,
-        "time::from::nanos" => Kind::Datetime,
-        "time::from::secs" => Kind::Datetime,
-        "time::from::unix" => Kind::Datetime,
-
-        // `duration::` functions
-        "duration::days" => Kind::Number,
-        "duration::hours" => Kind::Number,
-        "duration::micros" => Kind::Number,
-        "duration::millis" => Kind::Number,
-        "duration::mins" => Kind::Number,
-        "duration::nanos" => Kind::Number,
-        "duration::secs" => Kind::Number,
-        "duration::weeks" => Kind::Number,
-        "duration::years" => Kind::Number,
-        "duration::from::days" => Kind::Duration,
-        "duration::from::hours" => Kind::Duration,
-        "duration::from::micros" => Kind::Duration,
-        "duration::from::millis" => Kind::Duration,
-        "duration::from::mins" => Kind::Duration,
-        "duration::from::nanos" => Kind::Duration,
-        "duration::from::secs" => Kind::Duration,
-        "duration::from::weeks" => Kind::Duration,
-
-        // `crypto::` functions
-        "crypto::md5" => Kind::String,
-        "crypto::sha1" => Kind::String,
-        "crypto::sha256" => Kind::String,
-        "crypto::sha512" => Kind::String,
-        "crypto::argon2::compare" => Kind::Bool,
-        "crypto::argon2::generate" => Kind::String,
-        "crypto::bcrypt::compare" => Kind::Bool,
-        "crypto::bcrypt::generate" => Kind::String,
-        "crypto::pbkdf2::compare" => Kind::Bool,
-        "crypto::pbkdf2::generate" => Kind::String,
-        "crypto::scrypt::compare" => Kind::Bool,
-        "crypto::scrypt::generate" => Kind::String,
-
-        // `meta::` functions
-        "meta::id" => Kind::Any, // TODO: should this be a string?
-        "meta::type" => Kind::String,
-
-        // TODO: add more functions
-        "array::len" => Kind::Number,
-        // - `array::`
-        // - `encoding::`
-        // - `geo::`
-        // - `http::`
-        // - `object::`
-        // - `parse::`
-        // - `rand::`
-        // - `search::`
-        // - `session::`
-        // - `sleep::`
-        // - `string::`
-        // - `type::`
-        // - `vector::`
-        // - ``
-        _ => anyhow::bail!("Unsupported normal function: {}", name),
-    })
+/// Check the arguments passed to a custom function against its declared argument types,
+/// inferring the type of any parameters that haven't been typed yet.
+fn check_custom_function_arguments(
+    state: &mut QueryState,
+    function: &InterpretedFunction,
+    values: &[Value],
+) -> Result<(), anyhow::Error> {
+    // trailing `option<...>` or `any` arguments can be omitted
+    let max_args = function.args.len();
+    let min_args = max_args
+        - function
+            .args
+            .iter()
+            .rev()
+            .take_while(|(_, kind)| matches!(kind, Kind::Option(_) | Kind::Any))
+            .count();
+
+    if values.len() < min_args || values.len() > max_args {
+        anyhow::bail!(
+            "Function `fn::{}` expects {} arguments, but {} were given",
+            function.name,
+            match min_args == max_args {
+                true => max_args.to_string(),
+                false => format!("{} to {}", min_args, max_args),
+            },
+            values.len()
+        );
+    }
+
+    for (value, (arg_name, arg_kind)) in values.iter().zip(function.args.iter()) {
+        match value {
+            Value::Param(param) if state.is_untyped(param.as_str()) => {
+                state.infer(param.as_str(), arg_kind.clone())?;
+            }
+            value => {
+                let value_kind = get_value_return_type(value, &BTreeMap::new(), state)?;
+
+                if !is_assignable(&value_kind, arg_kind) {
+                    anyhow::bail!(
+                        "Argument `${}` of function `fn::{}` expects `{}`, but got `{}`",
+                        arg_name,
+                        function.name,
+                        arg_kind.pretty_string(),
+                        value_kind.pretty_string()
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Get the return type of a built-in function from its signature, inferring the
+/// type of any untyped parameters passed to it from the types of its arguments
+fn get_normal_function_return_type(
+    state: &mut QueryState,
+    name: &str,
+    values: &[Value],
+    field_types: &BTreeMap<String, Kind>,
+) -> Result<Kind, anyhow::Error> {
+    let args = get_argument_kinds(state, values, field_types)?;
+    let signature = get_builtin_function_signature(name, values, &args, state)?;
+
+    let mut inferred = false;
+
+    for (index, value) in values.iter().enumerate() {
+        let Some(arg_kind) = signature.arg(index) else {
+            continue;
+        };
+
+        match value {
+            Value::Param(param) if state.is_untyped(param.as_str()) && arg_kind != &Kind::Any => {
+                state.infer(param.as_str(), arg_kind.clone())?;
+                inferred = true;
+            }
+            _ => {}
+        }
+    }
+
+    match inferred {
+        // the return type may depend on the types that were just inferred
+        true => {
+            let args = get_argument_kinds(state, values, field_types)?;
+            Ok(get_builtin_function_signature(name, values, &args, state)?.return_type)
+        }
+        false => Ok(signature.return_type),
+    }
+}
+
+fn get_argument_kinds(
+    state: &mut QueryState,
+    values: &[Value],
+    field_types: &BTreeMap<String, Kind>,
+) -> Result<Vec<Kind>, anyhow::Error> {
+    let mut kinds = Vec::new();
+
+    for value in values {
+        kinds.push(match value {
+            Value::Param(param) if state.is_untyped(param.as_str()) => Kind::Any,
+            // closures, eg: `array::map($values, |$value| $value * 2)`
+            Value::Closure(_) => Kind::Any,
+            // the return types of built-in functions depend on the types of their arguments,
+            // not their values
+            value => widen_literal(&get_value_return_type(value, field_types, state)?),
+        });
+    }
+
+    Ok(kinds)
 }
@@ -1,11 +1,18 @@
+mod block;
+mod builtin_function;
+mod condition;
+mod data;
+mod fetch;
 mod function;
 mod object;
+mod operator;
 mod return_types;
 mod schema;
 mod statements;
 mod utils;
 
 use crate::Kind;
+pub use data::get_idiom_type;
 pub use return_types::get_statement_fields;
 use return_types::get_value_return_type;
 use statements::*;
@@ -91,10 +98,13 @@ fn get_statement_return_type(
         Statement::Create(create) => get_create_statement_return_type(create, state)?,
         Statement::Insert(insert) => get_insert_statement_return_type(insert, state)?,
         Statement::Update(update) => get_update_statement_return_type(update, state)?,
+        Statement::Relate(relate) => get_relate_statement_return_type(relate, state)?,
         Statement::Output(output) => get_return_statement_return_type(output, state)?,
         Statement::Upsert(upsert) => get_upsert_statement_return_type(upsert, state)?,
         Statement::Value(value) => get_value_return_type(value, &BTreeMap::new(), state)?,
         Statement::Set(set) => interpret_let_statement(set, state)?,
+        Statement::Ifelse(ifelse) => get_ifelse_statement_return_type(ifelse, state)?,
+        Statement::Foreach(foreach) => get_foreach_statement_return_type(foreach, state)?,
         _ => anyhow::bail!("Unsupported statement type: `{}`", stmt),
     }))
 }
@@ -110,7 +120,9 @@ fn get_subquery_return_type(
         Subquery::Insert(insert) => get_insert_statement_return_type(insert, state),
         Subquery::Update(update) => get_update_statement_return_type(update, state),
         Subquery::Upsert(upsert) => get_upsert_statement_return_type(upsert, state),
+        Subquery::Relate(relate) => get_relate_statement_return_type(relate, state),
         Subquery::Value(value) => get_value_return_type(value, &BTreeMap::new(), state),
+        Subquery::Ifelse(ifelse) => get_ifelse_statement_return_type(ifelse, state),
         _ => anyhow::bail!("Unsupported subquery type: `{}`", subquery),
     }
 }
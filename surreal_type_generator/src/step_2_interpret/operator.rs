@@ -0,0 +1,170 @@
+use surrealdb::sql::{Literal, Operator};
+
+use crate::{kind, Kind, PrettyString};
+
+use super::utils::{
+    get_array_element_type, get_number_kind, union_kinds, widen_literal, widen_union,
+};
+
+/// Get the type of an arithmetic expression such as `a + b` or `a ** b`, following the
+/// coercion rules of SurrealDB, eg: `int + float` is a `float` and `datetime - datetime` is a `duration`
+pub fn get_binary_operation_type(
+    operator: &Operator,
+    left: &Kind,
+    right: &Kind,
+) -> Result<Kind, anyhow::Error> {
+    match (left, right) {
+        (Kind::Any, _) | (_, Kind::Any) => Ok(Kind::Any),
+        // operating on `NONE` is an error, so only the values that can be operated on are typed
+        (Kind::Option(inner), right) => get_binary_operation_type(operator, inner, right),
+        (left, Kind::Option(inner)) => get_binary_operation_type(operator, left, inner),
+        (Kind::Either(kinds), right) => {
+            let mut results = Vec::new();
+            for kind in kinds {
+                results.push(get_binary_operation_type(operator, kind, right)?);
+            }
+            Ok(union_kinds(results))
+        }
+        (left, Kind::Either(kinds)) => {
+            let mut results = Vec::new();
+            for kind in kinds {
+                results.push(get_binary_operation_type(operator, left, kind)?);
+            }
+            Ok(union_kinds(results))
+        }
+        (left, right) => {
+            match get_operation_type(operator, &widen_literal(left), &widen_literal(right)) {
+                Some(kind) => Ok(kind),
+                None => anyhow::bail!(
+                    "Cannot apply `{}` to `{}` and `{}`",
+                    operator,
+                    left.pretty_string(),
+                    right.pretty_string()
+                ),
+            }
+        }
+    }
+}
+
+/// Get the type of a negated value, eg: `-age`
+pub fn get_negation_type(kind: &Kind) -> Result<Kind, anyhow::Error> {
+    match kind {
+        Kind::Any => Ok(Kind::Any),
+        Kind::Option(inner) => get_negation_type(inner),
+        Kind::Either(kinds) => {
+            let mut results = Vec::new();
+            for kind in kinds {
+                results.push(get_negation_type(kind)?);
+            }
+            Ok(union_kinds(results))
+        }
+        kind if is_number(&widen_literal(kind)) => Ok(get_number_kind(kind)),
+        kind => anyhow::bail!("Cannot negate a value of type `{}`", kind.pretty_string()),
+    }
+}
+
+/// Get the type of a null coalescing expression, `a ?? b`, which is `b` when `a` is `NONE` or `NULL`
+pub fn get_null_coalescing_type(left: &Kind, right: &Kind) -> Kind {
+    if left == &Kind::Any {
+        return Kind::Any;
+    }
+
+    let mut kinds = Vec::new();
+
+    match collect_some_kinds(left, &mut kinds) {
+        true => {
+            kinds.push(right.clone());
+            widen_union(kinds)
+        }
+        false => left.clone(),
+    }
+}
+
+/// Collect the kinds that a value can be when it isn't `NONE` or `NULL`,
+/// returning whether it could be either of them
+fn collect_some_kinds(kind: &Kind, kinds: &mut Vec<Kind>) -> bool {
+    match kind {
+        Kind::Option(inner) => {
+            collect_some_kinds(inner, kinds);
+            true
+        }
+        Kind::Null => true,
+        Kind::Either(variants) => {
+            let mut is_none = false;
+            for variant in variants {
+                is_none |= collect_some_kinds(variant, kinds);
+            }
+            is_none
+        }
+        kind => {
+            kinds.push(kind.clone());
+            false
+        }
+    }
+}
+
+fn get_operation_type(operator: &Operator, left: &Kind, right: &Kind) -> Option<Kind> {
+    Some(match (operator, left, right) {
+        (
+            Operator::Add
+            | Operator::Sub
+            | Operator::Mul
+            | Operator::Div
+            | Operator::Rem
+            | Operator::Pow,
+            left,
+            right,
+        ) if is_number(left) && is_number(right) => {
+            get_number_operation_type(operator, left, right)
+        }
+        (Operator::Add, Kind::String, Kind::String) => Kind::String,
+        (Operator::Add | Operator::Sub, Kind::Datetime, Kind::Duration)
+        | (Operator::Add | Operator::Sub, Kind::Duration, Kind::Datetime) => Kind::Datetime,
+        (Operator::Add | Operator::Sub, Kind::Duration, Kind::Duration)
+        | (Operator::Sub, Kind::Datetime, Kind::Datetime) => Kind::Duration,
+        // `[1, 2] + [3]` concatenates the arrays
+        (Operator::Add, left, right) if is_array(left) && is_array(right) => {
+            kind!(Arr union_kinds(vec![
+                get_array_element_type(left).ok()?,
+                get_array_element_type(right).ok()?,
+            ]))
+        }
+        _ => return None,
+    })
+}
+
+fn get_number_operation_type(operator: &Operator, left: &Kind, right: &Kind) -> Kind {
+    match (operator, left, right) {
+        (Operator::Pow, Kind::Int, Kind::Int) => Kind::Int,
+        (Operator::Pow, Kind::Decimal, Kind::Int | Kind::Float | Kind::Decimal) => Kind::Decimal,
+        (Operator::Pow, Kind::Number, _) | (Operator::Pow, _, Kind::Number) => Kind::Number,
+        (Operator::Pow, ..) => Kind::Float,
+        // dividing by zero gives `NaN`, which is a float
+        (Operator::Div, left, right) => match promote_numbers(left, right) {
+            Kind::Float => Kind::Float,
+            _ => Kind::Number,
+        },
+        (_, left, right) => promote_numbers(left, right),
+    }
+}
+
+/// Integers are promoted to floats, and anything with a decimal is promoted to a decimal
+fn promote_numbers(left: &Kind, right: &Kind) -> Kind {
+    match (left, right) {
+        (Kind::Int, Kind::Int) => Kind::Int,
+        (Kind::Decimal, _) | (_, Kind::Decimal) => Kind::Decimal,
+        (Kind::Number, _) | (_, Kind::Number) => Kind::Number,
+        _ => Kind::Float,
+    }
+}
+
+fn is_number(kind: &Kind) -> bool {
+    matches!(kind, Kind::Int | Kind::Float | Kind::Decimal | Kind::Number)
+}
+
+fn is_array(kind: &Kind) -> bool {
+    matches!(
+        kind,
+        Kind::Array(..) | Kind::Set(..) | Kind::Literal(Literal::Array(_))
+    )
+}
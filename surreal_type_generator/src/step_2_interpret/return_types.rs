@@ -1,17 +1,24 @@
 use std::collections::{BTreeMap, HashSet};
 
 use surrealdb::sql::{
-    Cast, Constant, Expression, Field, Fields, Ident, Idiom, Literal, Operator, Param, Part, Value,
+    Cast, Constant, Dir, Expression, Field, Fields, Ident, Idiom, Literal, Number, Operator, Param,
+    Part, Table, Value,
 };
 
 use crate::{kind, Kind};
 
 use super::{
+    block::get_block_return_type,
+    condition::check_filter_cond,
     function::get_function_return_type,
     get_subquery_return_type,
     object::get_object_return_type,
+    operator::{get_binary_operation_type, get_negation_type, get_null_coalescing_type},
     schema::QueryState,
-    utils::{get_what_fields, merge_into_map_recursively},
+    utils::{
+        get_what_fields, merge_into_map_recursively, optional, union_kinds, widen_literal,
+        widen_union,
+    },
 };
 
 pub fn get_statement_fields<F>(
@@ -139,20 +146,28 @@ pub fn get_value_return_type(
             return_type
         }
         Value::Param(param) => get_parameter_return_type(param, state)?,
-        // TODO: These constants could potentially be represented as actual constants in the return types
-        Value::Strand(_) => Kind::String,
-        Value::Number(_) => Kind::Number,
+        // constants keep their value as a literal type, until they're widened by being merged
+        // with other values or assigned to a variable
+        Value::Strand(strand) => Kind::Literal(Literal::String(strand.clone())),
+        Value::Number(number) => match number {
+            Number::Int(_) => Kind::Literal(Literal::Number(*number)),
+            Number::Float(float) if float.is_finite() => Kind::Literal(Literal::Number(*number)),
+            Number::Float(_) => Kind::Float,
+            _ => Kind::Decimal,
+        },
         Value::Bool(_) => Kind::Bool,
         Value::Null => Kind::Null,
         Value::Datetime(_) => Kind::Datetime,
         Value::Duration(_) => Kind::Duration,
+        Value::Thing(thing) => Kind::Record(vec![thing.tb.clone().into()]),
         Value::None => Kind::Null,
-        Value::Function(func) => get_function_return_type(state, &func)?,
+        Value::Function(func) => get_function_return_type(state, func, field_types)?,
         Value::Expression(expr) => get_expression_return_type(expr, field_types, state)?,
         Value::Array(array) => {
             let mut return_types = HashSet::new();
             for value in &array.0 {
-                return_types.insert(get_value_return_type(value, field_types, state)?);
+                let return_type = get_value_return_type(value, field_types, state)?;
+                return_types.insert(widen_literal(&return_type));
             }
             // If there is more than one type, we muse use Either
             kind!(Arr match return_types.len() {
@@ -174,6 +189,7 @@ pub fn get_value_return_type(
             | Constant::MathFracPi6
             | Constant::MathFracPi8
             | Constant::MathInf
+            | Constant::MathNegInf
             | Constant::MathLn10
             | Constant::MathLn2
             | Constant::MathLog102
@@ -182,11 +198,12 @@ pub fn get_value_return_type(
             | Constant::MathLog2E
             | Constant::MathPi
             | Constant::MathSqrt2
-            | Constant::MathTau
-            | Constant::TimeEpoch => Kind::Number,
+            | Constant::MathTau => Kind::Float,
+            Constant::TimeEpoch => Kind::Datetime,
             _ => anyhow::bail!("Unsupported constant: {:?}", constant),
         },
         Value::Cast(box Cast { 0: kind, .. }) => kind.clone(),
+        Value::Block(block) => get_block_return_type(block, state)?,
         _ => anyhow::bail!("Unsupported value/expression: {}", expr),
     })
 }
@@ -202,8 +219,9 @@ pub fn get_expression_return_type(
             o: Operator::Not, ..
         } => Kind::Bool,
         Expression::Unary {
-            o: Operator::Neg, ..
-        } => anyhow::bail!("Unsupported unary operator"),
+            o: Operator::Neg,
+            v,
+        } => get_negation_type(&get_value_return_type(v, field_types, state)?)?,
 
         // logical binary expressions
         Expression::Binary {
@@ -211,7 +229,7 @@ pub fn get_expression_return_type(
         } => Kind::Bool,
         Expression::Binary {
             l,
-            o: Operator::Or,
+            o: Operator::Or | Operator::Tco,
             r,
         } => {
             let l = get_value_return_type(l, field_types, state)?;
@@ -220,78 +238,84 @@ pub fn get_expression_return_type(
             // If left is an `option<thing>` and right are both a `thing` we should return a `thing`
             // This is because, if left is an option, it is considered falsey, and right will be returned
             match (l, r) {
-                (Kind::Option(box left), right) => {
-                    if left == right {
-                        right
-                    } else {
-                        Kind::Either(vec![left, right])
-                    }
-                }
-                (left, right) => {
-                    if left == right {
-                        right
-                    } else {
-                        Kind::Either(vec![left, right])
-                    }
-                }
+                (Kind::Option(box left), right) => widen_union(vec![left, right]),
+                (left, right) => widen_union(vec![left, right]),
             }
         }
         Expression::Binary {
-            o: Operator::Equal, ..
-        } => Kind::Bool,
-        Expression::Binary {
-            o: Operator::NotEqual,
-            ..
-        } => Kind::Bool,
-        Expression::Binary {
-            o: Operator::Exact, ..
-        } => Kind::Bool,
+            l,
+            o: Operator::Nco,
+            r,
+        } => {
+            let l = get_value_return_type(l, field_types, state)?;
+            let r = get_value_return_type(r, field_types, state)?;
+
+            get_null_coalescing_type(&l, &r)
+        }
 
         // comparison binary expressions
         Expression::Binary {
-            o: Operator::LessThan,
-            ..
-        } => Kind::Bool,
-        Expression::Binary {
-            o: Operator::MoreThan,
-            ..
-        } => Kind::Bool,
-        Expression::Binary {
-            o: Operator::LessThanOrEqual,
+            o:
+                Operator::Equal
+                | Operator::Exact
+                | Operator::NotEqual
+                | Operator::AllEqual
+                | Operator::AnyEqual
+                | Operator::LessThan
+                | Operator::LessThanOrEqual
+                | Operator::MoreThan
+                | Operator::MoreThanOrEqual,
             ..
         } => Kind::Bool,
+
+        // fuzzy and full-text matching
         Expression::Binary {
-            o: Operator::MoreThanOrEqual,
+            o:
+                Operator::Like
+                | Operator::NotLike
+                | Operator::AllLike
+                | Operator::AnyLike
+                | Operator::Matches(_)
+                | Operator::Knn(..)
+                | Operator::Ann(..),
             ..
         } => Kind::Bool,
+
+        // containment binary expressions
         Expression::Binary {
-            o: Operator::Like, ..
-        } => Kind::Bool,
-        Expression::Binary {
-            o: Operator::NotLike,
+            o:
+                Operator::Contain
+                | Operator::NotContain
+                | Operator::ContainAll
+                | Operator::ContainAny
+                | Operator::ContainNone
+                | Operator::Inside
+                | Operator::NotInside
+                | Operator::AllInside
+                | Operator::AnyInside
+                | Operator::NoneInside
+                | Operator::Outside
+                | Operator::Intersects,
             ..
         } => Kind::Bool,
 
-        // TODO: arithmetic
+        // arithmetic binary expressions
         Expression::Binary {
             l,
-            o: Operator::Add,
+            o:
+                o @ (Operator::Add
+                | Operator::Sub
+                | Operator::Mul
+                | Operator::Div
+                | Operator::Rem
+                | Operator::Pow),
             r,
         } => {
             let l = get_value_return_type(l, field_types, state)?;
             let r = get_value_return_type(r, field_types, state)?;
 
-            match (&l, &r) {
-                (Kind::Number, Kind::Number) => Kind::Number,
-                (Kind::String, Kind::String) => Kind::String,
-                (Kind::Datetime, Kind::Datetime) => Kind::Datetime,
-                (Kind::Duration, Kind::Duration) => Kind::Duration,
-                _ => anyhow::bail!("Unsupported binary operation: {:?}", expr),
-            }
+            get_binary_operation_type(o, &l, &r)?
         }
-        // Expression
-        // TODO: short circuiting
-        // TODO: more (contains, any, etc, outside, inside, fuzzy match)
         _ => anyhow::bail!("Unsupported expression: {}", expr),
     })
 }
@@ -314,6 +338,8 @@ pub fn get_field_from_paths(
     match parts.first() {
         Some(Part::Field(field_name)) => match field_types.get(field_name.as_str()) {
             Some(return_type) => match_return_type(return_type, &parts, field_types, state),
+            // undeclared fields on schemaless tables can hold anything
+            None if state.schema.schema.is_schemaless_record(field_types) => Ok(Kind::Any),
             None => anyhow::bail!("Field not found: {}", field_name),
         },
         Some(Part::Start(Value::Param(Param {
@@ -327,9 +353,10 @@ pub fn get_field_from_paths(
         },
         Some(Part::Start(Value::Subquery(subquery))) => {
             let return_type = get_subquery_return_type(subquery, state)?;
-            match_return_type(&return_type, &parts[1..], field_types, state)
+            match_return_type(&return_type, &parts, field_types, state)
         }
         Some(Part::All) => Ok(kind!(Obj field_types.clone())),
+        Some(Part::Graph(_)) => get_graph_return_type(parts, state),
         Some(_) => anyhow::bail!("Unsupported path: {}", Idiom::from(parts)),
         // Some(_) => anyhow::bail!("Unsupported path: {:#?}", parts),
         // We're returning an actual object
@@ -337,6 +364,95 @@ pub fn get_field_from_paths(
     }
 }
 
+/// Resolve a graph traversal such as `->likes->post.title`.
+///
+/// Traversals alternate between edge and node tables, and always produce a flattened array
+/// of record links to the last table, or of that table's fields once projected further.
+fn get_graph_return_type(parts: &[Part], state: &mut QueryState) -> Result<Kind, anyhow::Error> {
+    let mut tables = Vec::new();
+    let mut is_edge = true;
+    let mut graph_parts = 0;
+
+    while let Some(Part::Graph(graph)) = parts.get(graph_parts) {
+        tables = match (graph.what.0.is_empty(), is_edge) {
+            (false, _) => graph.what.0.clone(),
+            (true, false) => get_graph_wildcard_tables(&graph.dir, &tables, state)?,
+            (true, true) => anyhow::bail!(
+                "Unable to infer the edge table of a `?` graph traversal: {}",
+                Idiom::from(parts)
+            ),
+        };
+
+        if let Some(cond) = &graph.cond {
+            for table in tables.iter() {
+                let table_fields = state.table_select_fields(table.as_str())?;
+                get_value_return_type(&cond.0, &table_fields, state)?;
+            }
+        }
+
+        is_edge = !is_edge;
+        graph_parts += 1;
+    }
+
+    let remaining_parts = &parts[graph_parts..];
+
+    if remaining_parts.is_empty() {
+        return Ok(kind!(Arr Kind::Record(tables)));
+    }
+
+    let mut return_types = Vec::new();
+    for table in tables.iter() {
+        let table_fields = state.table_select_fields(table.as_str())?;
+        return_types.push(get_field_from_paths(remaining_parts, &table_fields, state)?);
+    }
+
+    Ok(kind!(Arr union_kinds(return_types)))
+}
+
+/// Get the node tables a `?` wildcard can refer to, by reading the
+/// `in` and `out` record types of the edge tables that were traversed.
+fn get_graph_wildcard_tables(
+    dir: &Dir,
+    edge_tables: &[Table],
+    state: &mut QueryState,
+) -> Result<Vec<Table>, anyhow::Error> {
+    let edge_fields = match dir {
+        Dir::In => vec!["in"],
+        Dir::Out => vec!["out"],
+        Dir::Both => vec!["in", "out"],
+        #[allow(unreachable_patterns)]
+        _ => anyhow::bail!("Unsupported graph direction: {}", dir),
+    };
+
+    let mut tables = Vec::new();
+
+    for edge_table in edge_tables {
+        let table_fields = state.table_select_fields(edge_table.as_str())?;
+
+        for edge_field in edge_fields.iter() {
+            match table_fields.get(*edge_field) {
+                Some(Kind::Record(record_tables))
+                | Some(Kind::Option(box Kind::Record(record_tables)))
+                    if !record_tables.is_empty() =>
+                {
+                    for table in record_tables {
+                        if !tables.contains(table) {
+                            tables.push(table.clone());
+                        }
+                    }
+                }
+                _ => anyhow::bail!(
+                    "Unable to infer the target of a `?` graph traversal, as `{}` doesn't define a typed `{}` field",
+                    edge_table,
+                    edge_field
+                ),
+            }
+        }
+    }
+
+    Ok(tables)
+}
+
 fn match_return_type(
     return_type: &Kind,
     parts: &[Part],
@@ -388,38 +504,41 @@ fn match_return_type(
                 Kind::Record(tables.clone())
             }
         }
-        Kind::Option(return_type) => kind!(Opt(match_return_type(
-            return_type,
-            &parts,
-            field_types,
-            state,
-        )?)),
-        Kind::Array(return_type, ..) => match parts.first() {
-            Some(Part::Index(_)) => Kind::Option(Box::new(match_return_type(
-                return_type,
-                &parts,
+        Kind::Option(inner_type) => {
+            let inner_type = match_return_type(inner_type, &parts, field_types, state)?;
+            match parts.get(1) {
+                // indexing into a missing array is just as `NONE` as indexing out of bounds
+                Some(Part::Index(_) | Part::First | Part::Last) => optional(inner_type),
+                _ => kind!(Opt(inner_type)),
+            }
+        }
+        Kind::Array(element_type, _) | Kind::Set(element_type, _) => match parts.get(1) {
+            // `foo[1]` and `foo[$]` are `NONE` when the array is too short
+            Some(Part::Index(_) | Part::First | Part::Last) => optional(match_return_type(
+                element_type,
+                &parts[1..],
                 field_types,
                 state,
-            )?)),
+            )?),
             Some(Part::All) => kind!(Arr match_return_type(
-            return_type,
-            &parts,
-            field_types,
-            state,
-        )?),
-            Some(Part::Field(_)) => kind!(Arr match_return_type(
-            return_type,
-            &parts[1..],
-            field_types,
-            state,
-        )?),
-            Some(_) => return Err(anyhow::anyhow!("Unsupported path: {}", Idiom::from(parts))),
-            None => {
-                return Err(anyhow::anyhow!(
-                    "Tried to access array with no fields: {}",
-                    Idiom::from(parts)
-                ))
+                element_type,
+                &parts[1..],
+                field_types,
+                state,
+            )?),
+            // `foo[WHERE ...]` filters the array, but keeps its element type
+            Some(Part::Where(cond)) => {
+                check_filter_cond(cond, element_type, state)?;
+                match_return_type(return_type, &parts[1..], field_types, state)?
             }
+            // the rest of the path is applied to every element, eg: `addresses.city`
+            Some(_) => kind!(Arr match_return_type(
+                element_type,
+                &parts,
+                field_types,
+                state,
+            )?),
+            None => return_type.clone(),
         },
         Kind::Either(return_types) => {
             let mut return_types = return_types.clone();
@@ -1,16 +1,18 @@
 use std::{collections::BTreeMap, sync::Arc};
 
-use surrealdb::sql::{Block, Entry, Literal, Values};
+use surrealdb::sql::{Field, Literal, Value, Values};
 
 use crate::{
-    step_1_parse_sql::{parse_schema, FunctionParsed, SchemaParsed, ViewParsed},
-    Kind,
+    step_1_parse_sql::{parse_schema, FunctionParsed, ParamParsed, SchemaParsed, ViewParsed},
+    Kind, PrettyString,
 };
 
 use super::{
-    get_create_statement_return_type, get_delete_statement_return_type,
-    get_insert_statement_return_type, get_return_statement_return_type,
-    get_select_statement_return_type, get_statement_fields, get_update_statement_return_type,
+    block::get_block_return_type,
+    builtin_function::get_view_aggregate_return_type,
+    get_statement_fields,
+    return_types::get_value_return_type,
+    utils::{is_assignable, is_unsupported_error, widen_literal},
 };
 
 #[derive(Debug)]
@@ -40,8 +42,49 @@ impl QueryState {
         }
     }
 
-    pub fn infer(&mut self, key: &str, value: Kind) {
-        self.inferred_variables.insert(key.to_string(), value);
+    /// Record the inferred type of a parameter, reporting an error if an incompatible
+    /// type was already inferred for it elsewhere in the query.
+    ///
+    /// When both types are compatible, the narrower one is kept, eg: a parameter compared
+    /// against both a `string` and an `option<string>` has to be a `string`.
+    pub fn infer(&mut self, key: &str, value: Kind) -> Result<(), anyhow::Error> {
+        let is_narrower = match self.inferred_variables.get(key) {
+            None => true,
+            Some(existing) => {
+                match (
+                    is_assignable(&value, existing),
+                    is_assignable(existing, &value),
+                ) {
+                    (false, false) => anyhow::bail!(
+                        "Conflicting types inferred for parameter `${}`: `{}` and `{}`",
+                        key,
+                        existing.pretty_string(),
+                        value.pretty_string()
+                    ),
+                    (true, false) => true,
+                    (false, true) => false,
+                    // numeric kinds are interchangeable, so keep the more precise one
+                    (true, true) => matches!(existing, Kind::Number | Kind::Any),
+                }
+            }
+        };
+
+        if is_narrower {
+            self.inferred_variables.insert(key.to_string(), value);
+        }
+
+        Ok(())
+    }
+
+    /// Whether a parameter can have its type inferred, because it isn't a local
+    /// variable, a global, or explicitly typed with a cast.
+    pub fn is_untyped(&self, key: &str) -> bool {
+        !self
+            .stack_variables
+            .iter()
+            .any(|frame| frame.contains_key(key))
+            && !self.defined_variables.contains_key(key)
+            && !self.schema.global_variables.contains_key(key)
     }
 
     pub fn get(&self, key: &str) -> Option<Kind> {
@@ -129,10 +172,47 @@ pub fn interpret_schema(
     schema: &str,
     global_variables: BTreeMap<String, Kind>,
 ) -> Result<SchemaState, anyhow::Error> {
-    Ok(SchemaState {
+    let state = SchemaState {
         global_variables,
         schema: parse_schema(schema)?,
-    })
+    };
+
+    interpret_schema_params(state)
+}
+
+/// Type any `DEFINE PARAM` statements by their values, and add them as global variables.
+/// Explicit global variable casts take precedence over the inferred types.
+fn interpret_schema_params(state: SchemaState) -> Result<SchemaState, anyhow::Error> {
+    if state.schema.params.is_empty() {
+        return Ok(state);
+    }
+
+    let params = state.schema.params.clone();
+    let state = Arc::new(state);
+    let mut query_state = QueryState::new(state.clone(), BTreeMap::new());
+    let mut param_types = BTreeMap::new();
+
+    for ParamParsed { name, value } in params {
+        let kind = get_value_return_type(&value, &BTreeMap::new(), &mut query_state)
+            .map_err(|err| anyhow::anyhow!("Failed to interpret param `${}`: {}", name, err))?;
+        let kind = widen_literal(&kind);
+
+        // params can be referenced by any params defined after them
+        query_state.set_local(&name, kind.clone());
+        param_types.insert(name, kind);
+    }
+
+    drop(query_state);
+
+    let mut state = Arc::try_unwrap(state).map_err(|_| {
+        anyhow::anyhow!("Schema state is still referenced after interpreting params")
+    })?;
+
+    for (name, kind) in param_types {
+        state.global_variables.entry(name).or_insert(kind);
+    }
+
+    Ok(state)
 }
 
 fn interpret_function_parsed(
@@ -145,32 +225,39 @@ fn interpret_function_parsed(
         operation_state.set_local(&name, return_type.clone());
     }
 
-    let func = InterpretedFunction {
-        name: func.name,
-        args: func.arguments,
-        return_type: get_block_return_type(func.block, operation_state)?,
-    };
+    let inferred_return_type = get_block_return_type(&func.block, operation_state);
 
     operation_state.pop_stack_frame();
 
-    Ok(func)
-}
-
-fn get_block_return_type(block: Block, state: &mut QueryState) -> Result<Kind, anyhow::Error> {
-    for entry in block.0.into_iter() {
-        match entry {
-            Entry::Output(output) => return get_return_statement_return_type(&output, state),
-            Entry::Create(create) => return get_create_statement_return_type(&create, state),
-            Entry::Insert(insert) => return get_insert_statement_return_type(&insert, state),
-            Entry::Delete(delete) => return get_delete_statement_return_type(&delete, state),
-            Entry::Select(select) => return get_select_statement_return_type(&select, state),
-            Entry::Update(update) => return get_update_statement_return_type(&update, state),
-            // Entry::Upsert(upsert) => return get_upsert_statement_return_type(&upsert, state),
-            _ => anyhow::bail!("Entry type: {} has not been implemented", entry),
-        }
-    }
+    let return_type = match func.return_type {
+        // the declared return type always wins, but it has to agree with the function body
+        // when we're able to interpret it
+        Some(declared) => match inferred_return_type {
+            Ok(inferred) if !is_assignable(&inferred, &declared) => anyhow::bail!(
+                "Function `fn::{}` is declared to return `{}` but its body returns `{}`",
+                func.name,
+                declared.pretty_string(),
+                inferred.pretty_string()
+            ),
+            Ok(_) => declared,
+            // the body uses something we can't interpret yet, so trust the declaration
+            Err(error) if is_unsupported_error(&error) => declared,
+            Err(error) => {
+                return Err(error.context(format!(
+                    "Failed to interpret the body of function `fn::{}`",
+                    func.name
+                )))
+            }
+        },
+        // constants returned from the body are widened, like they are when assigned with `LET`
+        None => widen_literal(&inferred_return_type?),
+    };
 
-    Ok(Kind::Null)
+    Ok(InterpretedFunction {
+        name: func.name,
+        args: func.arguments,
+        return_type,
+    })
 }
 
 fn get_view_table(
@@ -187,6 +274,25 @@ fn get_view_table(
             // add the implicit id field
             fields.insert("id".into(), Kind::Record(vec![view.name.clone().into()]));
 
+            if view.groups.is_some() {
+                for field in view.expr.iter() {
+                    if let Field::Single {
+                        expr: Value::Function(func),
+                        alias: Some(alias),
+                    } = field
+                    {
+                        if let (Some(name), Some(return_type)) =
+                            (func.name(), fields.remove(&alias.to_string()))
+                        {
+                            fields.insert(
+                                alias.to_string(),
+                                get_view_aggregate_return_type(name, return_type),
+                            );
+                        }
+                    }
+                }
+            }
+
             Ok(fields)
         }
         Kind::Either(..) => anyhow::bail!("Multiple tables in view are not currently supported"),
@@ -2,7 +2,13 @@ use surrealdb::sql::{statements::CreateStatement, Data, Fields, Output, Value, V
 
 use crate::{
     kind,
-    step_2_interpret::{get_statement_fields, schema::QueryState, utils::get_value_table},
+    step_1_parse_sql::{RecordChange, TableParsed},
+    step_2_interpret::{
+        data::{get_diff_type, validate_object_content, validate_set_expressions},
+        get_statement_fields,
+        schema::QueryState,
+        utils::get_value_table,
+    },
     Kind,
 };
 
@@ -17,7 +23,7 @@ pub fn get_create_statement_return_type(
         Some(Output::After) | None => get_create_fields(create, state, None)?,
         Some(Output::Before | Output::Null) => Kind::Null,
         Some(Output::None) => Kind::Null,
-        Some(Output::Diff) => anyhow::bail!("Create with returned diff is not currently supported"),
+        Some(Output::Diff) => kind!(Arr get_diff_type(&create.what, RecordChange::Create, state)?),
         Some(Output::Fields(fields)) => get_create_fields(create, state, Some(fields))?,
         #[allow(unreachable_patterns)]
         _ => anyhow::bail!("Unknown CREATE statement type: {}", create),
@@ -73,13 +79,19 @@ fn validate_data_type(
             }
 
             if tables.len() == 1 {
-                state.infer(param.0.as_str(), tables.pop().unwrap());
+                state.infer(param.0.as_str(), tables.pop().unwrap())?;
             } else if tables.len() > 1 {
-                state.infer(&param.0.as_str(), Kind::Either(tables));
+                state.infer(&param.0.as_str(), Kind::Either(tables))?;
             }
 
             Ok(())
         }
+        Data::ContentExpression(Value::Object(object)) => {
+            validate_object_content(what, object, TableParsed::compute_create_fields, state)
+        }
+        Data::SetExpression(sets) => {
+            validate_set_expressions(what, sets, TableParsed::compute_create_fields, state)
+        }
         // TODO: support other data types and variable inference
         _ => Ok(()),
     }
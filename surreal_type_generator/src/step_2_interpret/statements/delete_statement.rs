@@ -2,7 +2,10 @@ use surrealdb::sql::{statements::DeleteStatement, Fields, Kind, Output};
 
 use crate::{
     kind,
-    step_2_interpret::{get_statement_fields, schema::QueryState},
+    step_1_parse_sql::RecordChange,
+    step_2_interpret::{
+        condition::infer_cond_params, data::get_diff_type, get_statement_fields, schema::QueryState,
+    },
 };
 
 pub fn get_delete_statement_return_type(
@@ -15,7 +18,7 @@ pub fn get_delete_statement_return_type(
         Some(Output::After) => Kind::Null,
         Some(Output::Before) => get_delete_fields(delete, state, None)?,
         Some(Output::Null) => Kind::Null,
-        Some(Output::Diff) => Err(anyhow::anyhow!("Delete with returned diff not supported"))?,
+        Some(Output::Diff) => kind!(Arr get_diff_type(&delete.what, RecordChange::Delete, state)?),
         Some(Output::Fields(fields)) => get_delete_fields(delete, state, Some(fields))?,
         Some(Output::None) => Kind::Null,
         None => Kind::Null,
@@ -26,6 +29,10 @@ pub fn get_delete_statement_return_type(
         )))?,
     };
 
+    if let Some(cond) = &delete.cond {
+        infer_cond_params(&delete.what, cond, state)?;
+    }
+
     if is_only {
         Ok(return_type)
     } else {
@@ -0,0 +1,45 @@
+use std::collections::BTreeMap;
+
+use surrealdb::sql::{statements::ForeachStatement, Value};
+
+use crate::{
+    step_2_interpret::{
+        block::{get_block_flow, BlockFlow},
+        return_types::get_value_return_type,
+        schema::QueryState,
+        utils::get_array_element_type,
+    },
+    Kind,
+};
+
+pub fn get_foreach_statement_return_type(
+    foreach: &ForeachStatement,
+    state: &mut QueryState,
+) -> Result<Kind, anyhow::Error> {
+    Ok(get_foreach_statement_flow(foreach, state)?.into_return_type())
+}
+
+pub fn get_foreach_statement_flow(
+    foreach: &ForeachStatement,
+    state: &mut QueryState,
+) -> Result<BlockFlow, anyhow::Error> {
+    let item_type = match &foreach.range {
+        // ranges such as `1..10` iterate over integers
+        Value::Range(_) => Kind::Int,
+        range => get_array_element_type(&get_value_return_type(range, &BTreeMap::new(), state)?)?,
+    };
+
+    state.push_stack_frame();
+    state.set_local(&foreach.param.0, item_type);
+
+    let body = get_block_flow(&foreach.block, state);
+
+    state.pop_stack_frame();
+
+    // a `FOR` statement itself always evaluates to `NONE`, as the loop may not run at all,
+    // but a `RETURN` inside of the loop exits the surrounding block
+    Ok(BlockFlow {
+        returns: body?.returns,
+        completes: Some(Kind::Null),
+    })
+}
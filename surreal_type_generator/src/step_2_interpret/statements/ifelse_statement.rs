@@ -0,0 +1,85 @@
+use std::collections::BTreeMap;
+
+use surrealdb::sql::{statements::IfelseStatement, Value};
+
+use crate::{
+    step_2_interpret::{
+        block::{get_block_flow, BlockFlow},
+        return_types::get_value_return_type,
+        schema::QueryState,
+        utils::widen_union,
+    },
+    Kind,
+};
+
+pub fn get_ifelse_statement_return_type(
+    ifelse: &IfelseStatement,
+    state: &mut QueryState,
+) -> Result<Kind, anyhow::Error> {
+    Ok(get_ifelse_statement_flow(ifelse, state)?.into_return_type())
+}
+
+pub fn get_ifelse_statement_flow(
+    ifelse: &IfelseStatement,
+    state: &mut QueryState,
+) -> Result<BlockFlow, anyhow::Error> {
+    let mut branches = Vec::new();
+
+    for (condition, then) in ifelse.exprs.iter() {
+        branches.push(get_branch_flow(Some(condition), then, state)?);
+    }
+
+    match &ifelse.close {
+        Some(close) => branches.push(get_branch_flow(None, close, state)?),
+        // without an `ELSE` branch, the statement evaluates to `NONE` when no condition matches
+        None => branches.push(BlockFlow::completes(Kind::Null)),
+    }
+
+    let mut returns = Vec::new();
+    let mut completes = Vec::new();
+
+    for branch in branches {
+        returns.extend(branch.returns);
+        completes.extend(branch.completes);
+    }
+
+    Ok(BlockFlow {
+        returns,
+        completes: match completes.is_empty() {
+            // every branch exits early
+            true => None,
+            false => Some(widen_union(completes)),
+        },
+    })
+}
+
+fn get_branch_flow(
+    condition: Option<&Value>,
+    then: &Value,
+    state: &mut QueryState,
+) -> Result<BlockFlow, anyhow::Error> {
+    // each branch gets its own stack frame so `LET` statements don't leak into other branches
+    state.push_stack_frame();
+
+    let flow = match condition {
+        Some(condition) => get_value_return_type(condition, &BTreeMap::new(), state)
+            .and_then(|_| get_then_flow(then, state)),
+        None => get_then_flow(then, state),
+    };
+
+    state.pop_stack_frame();
+
+    flow
+}
+
+fn get_then_flow(then: &Value, state: &mut QueryState) -> Result<BlockFlow, anyhow::Error> {
+    match then {
+        // a `RETURN` inside of a branch exits the surrounding block, not just the branch
+        Value::Block(block) => get_block_flow(block, state),
+        then => Ok(BlockFlow::completes(get_value_return_type(
+            then,
+            &BTreeMap::new(),
+            state,
+        )?)),
+    }
+}
@@ -1,8 +1,16 @@
-use surrealdb::sql::{statements::InsertStatement, Data, Fields, Output, Value};
+use surrealdb::sql::{
+    statements::InsertStatement, Data, Fields, Idiom, Object, Operator, Output, Part, Value,
+};
 
 use crate::{
     kind,
-    step_2_interpret::{get_statement_fields, schema::QueryState, utils::get_value_table},
+    step_1_parse_sql::{RecordChange, TableParsed},
+    step_2_interpret::{
+        data::{get_diff_type, validate_object_content, validate_set_expressions, ComputeFields},
+        get_statement_fields,
+        schema::QueryState,
+        utils::get_value_table,
+    },
     Kind,
 };
 
@@ -15,17 +23,44 @@ pub fn get_insert_statement_return_type(
         None => anyhow::bail!("Expected table name"),
     };
 
+    // with `ON DUPLICATE KEY UPDATE`, records that already exist are updated instead
+    let is_upsert = insert.update.is_some();
+
     let return_type = match &insert.output {
-        Some(Output::After) | None => get_insert_fields(&into, state, None)?,
+        Some(Output::After) | None => get_insert_fields(into, state, None, is_upsert)?,
+        Some(Output::Before) if is_upsert => Kind::Either(vec![
+            get_insert_fields(into, state, None, is_upsert)?,
+            Kind::Null,
+        ]),
         Some(Output::Before | Output::Null) => Kind::Null,
         Some(Output::None) => Kind::Null,
-        Some(Output::Diff) => anyhow::bail!("Insert with returned diff is not currently supported"),
-        Some(Output::Fields(fields)) => get_insert_fields(&into, state, Some(fields))?,
+        Some(Output::Diff) => kind!(Arr get_diff_type(
+            std::slice::from_ref(into),
+            match is_upsert {
+                true => RecordChange::Upsert,
+                false => RecordChange::Create,
+            },
+            state
+        )?),
+        Some(Output::Fields(fields)) => get_insert_fields(into, state, Some(fields), is_upsert)?,
         #[allow(unreachable_patterns)]
         _ => anyhow::bail!("Unknown INSERT statement type: {}", insert),
     };
 
-    validate_data_type(state, &into, &insert.data)?;
+    let compute_fields: ComputeFields = match insert.relation {
+        true => TableParsed::compute_relation_create_fields,
+        false => TableParsed::compute_create_fields,
+    };
+
+    validate_data_type(state, into, &insert.data, compute_fields, insert.relation)?;
+
+    match &insert.update {
+        Some(Data::UpdateExpression(sets)) => {
+            validate_update_data(state, into, sets, compute_fields)?
+        }
+        Some(_) => anyhow::bail!("Unsupported ON DUPLICATE KEY UPDATE clause: {}", insert),
+        None => {}
+    }
 
     Ok(kind!(Arr return_type))
 }
@@ -34,18 +69,60 @@ fn get_insert_fields(
     table: &Value,
     state: &mut QueryState,
     fields: Option<&Fields>,
+    is_upsert: bool,
 ) -> Result<Kind, anyhow::Error> {
     get_statement_fields(&[table.clone()], state, fields, |fields, state| {
         state.set_local("after", kind!(Obj fields.clone()));
-        state.set_local("before", kind!(Null));
+        state.set_local(
+            "before",
+            match is_upsert {
+                true => kind!(Either [kind!(Obj fields.clone()), kind!(Null)]),
+                false => kind!(Null),
+            },
+        );
         state.set_local("this", kind!(Obj fields.clone()));
     })
 }
 
+/// Interpret the `SET` assignments of `ON DUPLICATE KEY UPDATE`, where `$input`
+/// is the record that was attempted to be inserted
+fn validate_update_data(
+    state: &mut QueryState,
+    table: &Value,
+    sets: &[(Idiom, Operator, Value)],
+    compute_fields: ComputeFields,
+) -> Result<(), anyhow::Error> {
+    let table_name = get_value_table(table, state)?;
+
+    let input = match state.schema.schema.tables.get(&table_name) {
+        Some(table) => kind!(Obj compute_fields(table)?),
+        None => anyhow::bail!(
+            "Trying to insert a record into a non-existent table: {}",
+            table_name
+        ),
+    };
+
+    state.push_stack_frame();
+    state.set_local("input", input);
+
+    let result = validate_set_expressions(
+        std::slice::from_ref(table),
+        sets,
+        TableParsed::compute_update_fields,
+        state,
+    );
+
+    state.pop_stack_frame();
+
+    result
+}
+
 fn validate_data_type(
     state: &mut QueryState,
     table: &Value,
     data: &Data,
+    compute_fields: ComputeFields,
+    is_relation: bool,
 ) -> Result<(), anyhow::Error> {
     match data {
         Data::SingleExpression(Value::Param(param)) => {
@@ -56,7 +133,7 @@ fn validate_data_type(
 
             match state.schema.schema.tables.get(&table_name) {
                 Some(table) => {
-                    let insert_fields = kind!(Obj table.compute_create_fields()?);
+                    let insert_fields = kind!(Obj compute_fields(table)?);
 
                     // can insert multiple or a single record
                     tables.push(kind!(Either[kind!(Arr insert_fields.clone()), insert_fields]));
@@ -69,9 +146,65 @@ fn validate_data_type(
             // }
 
             if tables.len() == 1 {
-                state.infer(&param.0.as_str(), tables.pop().unwrap());
+                state.infer(&param.0.as_str(), tables.pop().unwrap())?;
             } else if tables.len() > 1 {
-                state.infer(&param.0.as_str(), Kind::Either(tables));
+                state.infer(&param.0.as_str(), Kind::Either(tables))?;
+            }
+
+            Ok(())
+        }
+        Data::SingleExpression(Value::Object(object)) => {
+            validate_insert_object(state, table, object, compute_fields, is_relation)
+        }
+        // inserting multiple records at once
+        Data::SingleExpression(Value::Array(array)) => {
+            for value in array.iter() {
+                if let Value::Object(object) = value {
+                    validate_insert_object(state, table, object, compute_fields, is_relation)?;
+                }
+            }
+
+            Ok(())
+        }
+        // `INSERT INTO user (name, email) VALUES ($name, $email), ("jane", NONE)`
+        Data::ValuesExpression(rows) => {
+            let table_name = get_value_table(table, state)?;
+            let create_fields = match state.schema.schema.tables.get(&table_name) {
+                Some(table) => compute_fields(table)?,
+                None => anyhow::bail!(
+                    "Trying to insert a record into a non-existent table: {}",
+                    table_name
+                ),
+            };
+
+            for row in rows.iter() {
+                let sets = row
+                    .iter()
+                    .map(|(idiom, value)| (idiom.clone(), Operator::Equal, value.clone()))
+                    .collect::<Vec<_>>();
+
+                validate_set_expressions(
+                    std::slice::from_ref(table),
+                    &sets,
+                    compute_fields,
+                    state,
+                )?;
+
+                let columns = row
+                    .iter()
+                    .filter_map(|(idiom, _)| match idiom.first() {
+                        Some(Part::Field(name)) => Some(name.as_str()),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+
+                // fields that are optional, have a default, or can be any value can be left out
+                let required = create_fields
+                    .iter()
+                    .filter(|(_, kind)| !matches!(kind, Kind::Option(_) | Kind::Any))
+                    .map(|(name, _)| name.as_str());
+
+                check_required_fields(&columns, required, &table_name)?;
             }
 
             Ok(())
@@ -80,3 +213,36 @@ fn validate_data_type(
         _ => Ok(()),
     }
 }
+
+fn validate_insert_object(
+    state: &mut QueryState,
+    table: &Value,
+    object: &Object,
+    compute_fields: ComputeFields,
+    is_relation: bool,
+) -> Result<(), anyhow::Error> {
+    if is_relation {
+        let keys = object.keys().map(String::as_str).collect::<Vec<_>>();
+        check_required_fields(&keys, ["in", "out"], &get_value_table(table, state)?)?;
+    }
+
+    validate_object_content(std::slice::from_ref(table), object, compute_fields, state)
+}
+
+fn check_required_fields<'a>(
+    fields: &[&str],
+    required: impl IntoIterator<Item = &'a str>,
+    table_name: &str,
+) -> Result<(), anyhow::Error> {
+    for name in required {
+        if !fields.contains(&name) {
+            anyhow::bail!(
+                "Missing required field `{}` when inserting into `{}`",
+                name,
+                table_name
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -1,7 +1,9 @@
 use std::collections::BTreeMap;
 use surrealdb::sql::{statements::SetStatement, Kind};
 
-use crate::step_2_interpret::{return_types::get_value_return_type, QueryState};
+use crate::step_2_interpret::{
+    return_types::get_value_return_type, utils::widen_literal, QueryState,
+};
 
 pub fn interpret_let_statement(
     let_statement: &SetStatement,
@@ -13,7 +15,7 @@ pub fn interpret_let_statement(
         } => kind.clone(),
         SetStatement {
             kind: None, what, ..
-        } => get_value_return_type(what, &BTreeMap::new(), state)?,
+        } => widen_literal(&get_value_return_type(what, &BTreeMap::new(), state)?),
     };
 
     state.set_local(&let_statement.name, kind);
@@ -1,7 +1,10 @@
 mod create_statement;
 mod delete_statement;
+mod foreach_statement;
+mod ifelse_statement;
 mod insert_statement;
 mod let_statement;
+mod relate_statement;
 mod return_statement;
 mod select_statement;
 mod update_statement;
@@ -9,8 +12,11 @@ mod upsert_statement;
 
 pub use create_statement::get_create_statement_return_type;
 pub use delete_statement::get_delete_statement_return_type;
+pub use foreach_statement::{get_foreach_statement_flow, get_foreach_statement_return_type};
+pub use ifelse_statement::{get_ifelse_statement_flow, get_ifelse_statement_return_type};
 pub use insert_statement::get_insert_statement_return_type;
 pub use let_statement::interpret_let_statement;
+pub use relate_statement::get_relate_statement_return_type;
 pub use return_statement::get_return_statement_return_type;
 pub use select_statement::get_select_statement_return_type;
 pub use update_statement::get_update_statement_return_type;
@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+
+use surrealdb::sql::{statements::RelateStatement, Data, Fields, Literal, Output, Value};
+
+use crate::{
+    kind,
+    step_1_parse_sql::{RecordChange, TableParsed},
+    step_2_interpret::{
+        data::{get_diff_type, validate_object_content, validate_set_expressions},
+        get_statement_fields,
+        return_types::get_value_return_type,
+        schema::QueryState,
+        utils::get_value_table,
+    },
+    Kind,
+};
+
+pub fn get_relate_statement_return_type(
+    relate: &RelateStatement,
+    state: &mut QueryState,
+) -> Result<Kind, anyhow::Error> {
+    let is_only = relate.only;
+
+    let return_type = match &relate.output {
+        // default return type
+        Some(Output::After) | None => get_relate_fields(relate, state, None)?,
+        Some(Output::Before | Output::Null) => Kind::Null,
+        Some(Output::None) => Kind::Null,
+        Some(Output::Diff) => kind!(Arr get_diff_type(
+            std::slice::from_ref(&relate.kind),
+            RecordChange::Create,
+            state
+        )?),
+        Some(Output::Fields(fields)) => get_relate_fields(relate, state, Some(fields))?,
+        #[allow(unreachable_patterns)]
+        _ => anyhow::bail!("Unknown RELATE statement type: {}", relate),
+    };
+
+    if let Some(content) = &relate.data {
+        validate_data_type(state, &relate.kind, content)?;
+    }
+
+    if is_only {
+        Ok(return_type)
+    } else {
+        Ok(kind!(Arr return_type))
+    }
+}
+
+fn get_relate_fields(
+    relate: &RelateStatement,
+    state: &mut QueryState,
+    fields: Option<&Fields>,
+) -> Result<Kind, anyhow::Error> {
+    let in_type = get_relate_target_type(&relate.from, state)?;
+    let out_type = get_relate_target_type(&relate.with, state)?;
+
+    get_statement_fields(
+        std::slice::from_ref(&relate.kind),
+        state,
+        // we always want to go through the projection, so the `in` and `out` fields get replaced
+        Some(fields.unwrap_or(&Fields::all())),
+        |fields, state| {
+            if let Some(in_type) = &in_type {
+                fields.insert("in".into(), in_type.clone());
+            }
+            if let Some(out_type) = &out_type {
+                fields.insert("out".into(), out_type.clone());
+            }
+
+            state.set_local("after", kind!(Obj fields.clone()));
+            state.set_local("before", Kind::Null);
+            state.set_local("this", kind!(Obj fields.clone()));
+        },
+    )
+}
+
+/// Get the record type of the `in` or `out` side of a `RELATE` statement,
+/// if it can be determined from the statement itself.
+fn get_relate_target_type(
+    target: &Value,
+    state: &mut QueryState,
+) -> Result<Option<Kind>, anyhow::Error> {
+    let target_type = match target {
+        Value::Thing(thing) => return Ok(Some(Kind::Record(vec![thing.tb.clone().into()]))),
+        target => get_value_return_type(target, &BTreeMap::new(), state)?,
+    };
+
+    Ok(get_record_type(&target_type))
+}
+
+fn get_record_type(kind: &Kind) -> Option<Kind> {
+    match kind {
+        Kind::Record(tables) if !tables.is_empty() => Some(Kind::Record(tables.clone())),
+        // relating from the results of a query, eg: `RELATE (SELECT * FROM user)->likes->$post`
+        Kind::Literal(Literal::Object(fields)) => fields.get("id").and_then(get_record_type),
+        Kind::Array(inner, ..) | Kind::Option(inner) => get_record_type(inner),
+        Kind::Either(kinds) => {
+            let mut tables = Vec::new();
+            for kind in kinds {
+                match get_record_type(kind) {
+                    Some(Kind::Record(record_tables)) => {
+                        for table in record_tables {
+                            if !tables.contains(&table) {
+                                tables.push(table);
+                            }
+                        }
+                    }
+                    _ => return None,
+                }
+            }
+            match tables.is_empty() {
+                true => None,
+                false => Some(Kind::Record(tables)),
+            }
+        }
+        _ => None,
+    }
+}
+
+fn validate_data_type(
+    state: &mut QueryState,
+    what: &Value,
+    data: &Data,
+) -> Result<(), anyhow::Error> {
+    match data {
+        Data::ContentExpression(Value::Param(param)) => {
+            let table_name = get_value_table(what, state)?;
+
+            match state.schema.schema.tables.get(&table_name) {
+                Some(table) => {
+                    let create_fields = table.compute_relate_create_fields()?;
+                    state.infer(param.0.as_str(), kind!(Obj create_fields))?;
+                }
+                None => anyhow::bail!(
+                    "Trying to relate a record with an unknown or view table: {}",
+                    table_name
+                ),
+            }
+
+            Ok(())
+        }
+        Data::ContentExpression(Value::Object(object)) => validate_object_content(
+            std::slice::from_ref(what),
+            object,
+            TableParsed::compute_relate_create_fields,
+            state,
+        ),
+        Data::SetExpression(sets) => validate_set_expressions(
+            std::slice::from_ref(what),
+            sets,
+            TableParsed::compute_relate_create_fields,
+            state,
+        ),
+        _ => Ok(()),
+    }
+}
@@ -3,7 +3,9 @@ use std::collections::BTreeMap;
 use surrealdb::sql::statements::OutputStatement;
 
 use crate::{
-    step_2_interpret::{return_types::get_value_return_type, schema::QueryState},
+    step_2_interpret::{
+        fetch::get_fetch_type, return_types::get_value_return_type, schema::QueryState,
+    },
     Kind,
 };
 
@@ -16,9 +18,12 @@ pub fn get_return_statement_return_type(
             what, fetch: None, ..
         } => get_value_return_type(what, &BTreeMap::new(), state)?,
         OutputStatement {
-            what: _,
-            fetch: Some(_),
+            what,
+            fetch: Some(fetchs),
             ..
-        } => anyhow::bail!("TODO: Support fetch fields"),
+        } => {
+            let return_type = get_value_return_type(what, &BTreeMap::new(), state)?;
+            get_fetch_type(return_type, fetchs, state)?
+        }
     })
 }
@@ -1,15 +1,22 @@
 use crate::{
     kind,
-    step_2_interpret::{return_types::get_statement_fields, schema::QueryState},
-    Kind,
+    step_2_interpret::{
+        condition::infer_cond_params, fetch::get_fetch_type, return_types::get_statement_fields,
+        schema::QueryState, utils::get_array_element_type,
+    },
+    Kind, PrettyString,
 };
 
-use surrealdb::sql::statements::SelectStatement;
+use surrealdb::sql::{statements::SelectStatement, Idiom, Literal, Part};
 
 pub fn get_select_statement_return_type(
     select: &SelectStatement,
     state: &mut QueryState,
 ) -> Result<Kind, anyhow::Error> {
+    if let Some(cond) = &select.cond {
+        infer_cond_params(&select.what, cond, state)?;
+    }
+
     if select.only {
         // only will error if the select statement returns nothing
         Ok(get_select_fields(select, state)?)
@@ -22,7 +29,91 @@ fn get_select_fields(
     select: &SelectStatement,
     state: &mut QueryState,
 ) -> Result<Kind, anyhow::Error> {
-    get_statement_fields(&select.what, state, Some(&select.expr), |fields, state| {
-        state.set_local("this", kind!(Obj fields.clone()));
+    let mut return_type =
+        get_statement_fields(&select.what, state, Some(&select.expr), |fields, state| {
+            state.set_local("this", kind!(Obj fields.clone()));
+        })?;
+
+    if let Some(splits) = &select.split {
+        for split in splits.iter() {
+            return_type = get_split_type(&return_type, split, &split.0, state)?;
+        }
+    }
+
+    // records are fetched after the results have been split
+    if let Some(fetchs) = &select.fetch {
+        return_type = get_fetch_type(return_type, fetchs, state)?;
+    }
+
+    Ok(return_type)
+}
+
+/// `SPLIT tags` returns a row for each element of the `tags` array, so the
+/// type of the field in the result becomes the type of its elements
+fn get_split_type(
+    kind: &Kind,
+    split: &Idiom,
+    parts: &[Part],
+    state: &QueryState,
+) -> Result<Kind, anyhow::Error> {
+    let (name, rest) = match parts {
+        [] => {
+            return match kind {
+                // fields that are `NONE` are returned as they are
+                Kind::Option(inner) => Ok(kind!(Opt(get_split_type(inner, split, parts, state)?))),
+                Kind::Array(..) | Kind::Set(..) | Kind::Literal(Literal::Array(_)) | Kind::Any => {
+                    get_array_element_type(kind)
+                }
+                Kind::Either(kinds) if kinds.iter().all(is_array_kind) => {
+                    get_array_element_type(kind)
+                }
+                kind => anyhow::bail!(
+                    "Cannot split on `{}` of type `{}`, expected an array",
+                    split,
+                    kind.pretty_string()
+                ),
+            };
+        }
+        [Part::Field(name), rest @ ..] => (name, rest),
+        _ => anyhow::bail!("Unsupported SPLIT path: {}", split),
+    };
+
+    Ok(match kind {
+        Kind::Either(kinds) => Kind::Either(
+            kinds
+                .iter()
+                .map(|kind| get_split_type(kind, split, parts, state))
+                .collect::<Result<_, _>>()?,
+        ),
+        Kind::Option(inner) => kind!(Opt(get_split_type(inner, split, parts, state)?)),
+        Kind::Any | Kind::Object => Kind::Any,
+        Kind::Literal(Literal::Object(fields)) => {
+            let mut fields = fields.clone();
+
+            match fields.get(name.as_str()) {
+                Some(field) => {
+                    let split_type = get_split_type(field, split, rest, state)?;
+                    fields.insert(name.to_string(), split_type);
+                }
+                // schemaless tables can have fields of any type
+                None if state.schema.schema.is_schemaless_record(&fields) => {}
+                None => anyhow::bail!("Cannot split on `{}`, the field does not exist", split),
+            }
+
+            kind!(Obj fields)
+        }
+        kind => anyhow::bail!(
+            "Cannot split on `{}`, expected an object but got `{}`",
+            split,
+            kind.pretty_string()
+        ),
     })
 }
+
+fn is_array_kind(kind: &Kind) -> bool {
+    match kind {
+        Kind::Option(inner) => is_array_kind(inner),
+        Kind::Array(..) | Kind::Set(..) | Kind::Literal(Literal::Array(_)) => true,
+        _ => false,
+    }
+}
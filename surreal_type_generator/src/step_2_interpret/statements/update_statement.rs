@@ -1,8 +1,18 @@
-use surrealdb::sql::{statements::UpdateStatement, Data, Fields, Output, Values};
+use surrealdb::sql::{statements::UpdateStatement, Data, Fields, Output, Param, Value, Values};
 
 use crate::{
     kind,
-    step_2_interpret::{get_statement_fields, schema::QueryState},
+    step_1_parse_sql::{RecordChange, TableParsed},
+    step_2_interpret::{
+        condition::infer_cond_params,
+        data::{
+            get_diff_type, infer_patch_param, validate_object_content, validate_set_expressions,
+            ComputeFields,
+        },
+        get_statement_fields,
+        schema::QueryState,
+        utils::get_value_table,
+    },
     Kind,
 };
 
@@ -18,7 +28,7 @@ pub fn get_update_statement_return_type(
             Kind::Either(vec![get_update_fields(update, state, None)?, Kind::Null])
         }
         Some(Output::Null) => Kind::Null,
-        Some(Output::Diff) => Err(anyhow::anyhow!("Update with returned diff not supported"))?,
+        Some(Output::Diff) => kind!(Arr get_diff_type(&update.what, RecordChange::Update, state)?),
         Some(Output::Fields(fields)) => get_update_fields(update, state, Some(fields))?,
         Some(Output::None) => Kind::Null,
         #[allow(unreachable_patterns)]
@@ -33,6 +43,10 @@ pub fn get_update_statement_return_type(
         None => {}
     }
 
+    if let Some(cond) = &update.cond {
+        infer_cond_params(&update.what, cond, state)?;
+    }
+
     if is_only {
         Ok(return_type)
     } else {
@@ -60,33 +74,54 @@ fn validate_data_type(
     what: &Values,
     data: &Data,
 ) -> Result<(), anyhow::Error> {
-    let _ = state;
-    let _ = what;
     match data {
         Data::SetExpression(sets) => {
-            for _set in sets.iter() {
-                // TODO
-
-                // let mut tables = vec![];
-
-                // for table in what.iter() {
-                //     let table_name = match &table {
-                //         Value::Table(table) => table.0.as_str(),
-                //         _ => anyhow::bail!("Expected table name"),
-                //     };
-                // }
-
-                // if tables.len() == 1 {
-                //     state.infer(param.0.as_str(), tables.pop().unwrap());
-                // } else if tables.len() > 1 {
-                //     state.infer(&param.0.as_str(), Kind::Either(tables));
-                // }
-            }
-
-            Ok(())
+            validate_set_expressions(what, sets, TableParsed::compute_update_fields, state)
         }
+        Data::ContentExpression(Value::Object(object)) => {
+            validate_object_content(what, object, TableParsed::compute_update_fields, state)
+        }
+        Data::MergeExpression(Value::Object(object)) => {
+            validate_object_content(what, object, TableParsed::compute_merge_fields, state)
+        }
+        // `CONTENT` replaces the whole record, while `MERGE` only needs the fields being changed
+        Data::ContentExpression(Value::Param(param)) => {
+            infer_update_param(what, param, TableParsed::compute_update_fields, state)
+        }
+        Data::MergeExpression(Value::Param(param)) => {
+            infer_update_param(what, param, TableParsed::compute_merge_fields, state)
+        }
+        Data::PatchExpression(Value::Param(param)) => infer_patch_param(what, param, state),
         _ => Err(anyhow::anyhow!(
             "Unsupported data type for UPDATE statement"
         ))?,
     }
 }
+
+fn infer_update_param(
+    what: &Values,
+    param: &Param,
+    compute_fields: ComputeFields,
+    state: &mut QueryState,
+) -> Result<(), anyhow::Error> {
+    let mut tables = Vec::new();
+
+    for table in what.iter() {
+        let table_name = get_value_table(table, state)?;
+        match state.schema.schema.tables.get(&table_name) {
+            Some(table) => tables.push(kind!(Obj compute_fields(table)?)),
+            None => anyhow::bail!(
+                "Trying to update a record with an unknown or view table: {}",
+                table_name
+            ),
+        }
+    }
+
+    if tables.len() == 1 {
+        state.infer(param.0.as_str(), tables.pop().unwrap())?;
+    } else if tables.len() > 1 {
+        state.infer(param.0.as_str(), Kind::Either(tables))?;
+    }
+
+    Ok(())
+}
@@ -1,8 +1,18 @@
-use surrealdb::sql::{statements::UpsertStatement, Data, Fields, Output, Value, Values};
+use surrealdb::sql::{statements::UpsertStatement, Data, Fields, Output, Param, Value, Values};
 
 use crate::{
     kind,
-    step_2_interpret::{get_statement_fields, schema::QueryState, utils::get_value_table},
+    step_1_parse_sql::{RecordChange, TableParsed},
+    step_2_interpret::{
+        condition::infer_cond_params,
+        data::{
+            get_diff_type, infer_patch_param, validate_object_content, validate_set_expressions,
+            ComputeFields,
+        },
+        get_statement_fields,
+        schema::QueryState,
+        utils::get_value_table,
+    },
     Kind,
 };
 
@@ -18,7 +28,7 @@ pub fn get_upsert_statement_return_type(
             Kind::Either(vec![Kind::Null, get_upsert_fields(upsert, state, None)?])
         }
         Some(Output::None) => Kind::Null,
-        Some(Output::Diff) => Err(anyhow::anyhow!("Create with returned diff not supported"))?,
+        Some(Output::Diff) => kind!(Arr get_diff_type(&upsert.what, RecordChange::Upsert, state)?),
         Some(Output::Fields(fields)) => get_upsert_fields(upsert, state, Some(fields))?,
         #[allow(unreachable_patterns)]
         _ => Err(anyhow::anyhow!(format!(
@@ -32,6 +42,10 @@ pub fn get_upsert_statement_return_type(
         None => {}
     }
 
+    if let Some(cond) = &upsert.cond {
+        infer_cond_params(&upsert.what, cond, state)?;
+    }
+
     if is_only {
         Ok(return_type)
     } else {
@@ -45,37 +59,61 @@ pub fn validate_data_type(
     data: &Data,
 ) -> Result<(), anyhow::Error> {
     match data {
-        Data::MergeExpression(Value::Param(param))
-        | Data::ContentExpression(Value::Param(param)) => {
-            // we want to infer the type of this param by reading the table's required types and fields for insertion
-            let mut tables = Vec::new();
+        Data::ContentExpression(Value::Param(param)) => {
+            infer_upsert_param(what, param, TableParsed::compute_create_fields, state)
+        }
+        // `MERGE` only needs the fields being changed when the record already exists
+        Data::MergeExpression(Value::Param(param)) => {
+            infer_upsert_param(what, param, TableParsed::compute_upsert_merge_fields, state)
+        }
+        Data::ContentExpression(Value::Object(object)) => {
+            validate_object_content(what, object, TableParsed::compute_create_fields, state)
+        }
+        Data::MergeExpression(Value::Object(object)) => validate_object_content(
+            what,
+            object,
+            TableParsed::compute_upsert_merge_fields,
+            state,
+        ),
+        Data::SetExpression(sets) => {
+            validate_set_expressions(what, sets, TableParsed::compute_create_fields, state)
+        }
+        Data::PatchExpression(Value::Param(param)) => infer_patch_param(what, param, state),
+        // TODO: support other data types and variable inference
+        _ => Ok(()),
+    }
+}
 
-            for table in what.iter() {
-                let table_name = get_value_table(table, state)?;
-                match state.schema.schema.tables.get(&table_name) {
-                    Some(table) => {
-                        let create_fields = kind!(Obj table.compute_create_fields()?);
-                        tables
-                            .push(kind!(Either [create_fields.clone(), kind!(Arr create_fields)]));
-                    }
-                    None => anyhow::bail!(
-                        "Tried to create a record with an unknown or view table: {}",
-                        table_name
-                    ),
-                }
-            }
+fn infer_upsert_param(
+    what: &Values,
+    param: &Param,
+    compute_fields: ComputeFields,
+    state: &mut QueryState,
+) -> Result<(), anyhow::Error> {
+    // we want to infer the type of this param by reading the table's required types and fields for insertion
+    let mut tables = Vec::new();
 
-            if tables.len() == 1 {
-                state.infer(param.0.as_str(), tables.pop().unwrap());
-            } else if tables.len() > 1 {
-                state.infer(&param.0.as_str(), Kind::Either(tables));
+    for table in what.iter() {
+        let table_name = get_value_table(table, state)?;
+        match state.schema.schema.tables.get(&table_name) {
+            Some(table) => {
+                let fields = kind!(Obj compute_fields(table)?);
+                tables.push(kind!(Either [fields.clone(), kind!(Arr fields)]));
             }
-
-            Ok(())
+            None => anyhow::bail!(
+                "Tried to create a record with an unknown or view table: {}",
+                table_name
+            ),
         }
-        // TODO: support other data types and variable inference
-        _ => Ok(()),
     }
+
+    if tables.len() == 1 {
+        state.infer(param.0.as_str(), tables.pop().unwrap())?;
+    } else if tables.len() > 1 {
+        state.infer(&param.0.as_str(), Kind::Either(tables))?;
+    }
+
+    Ok(())
 }
 
 fn get_upsert_fields(
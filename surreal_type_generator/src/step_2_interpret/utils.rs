@@ -1,7 +1,7 @@
 use std::collections::BTreeMap;
 
 use crate::{kind, Kind};
-use surrealdb::sql::{Ident, Literal, Param, Part, Thing, Value};
+use surrealdb::sql::{Ident, Literal, Number, Param, Part, Thing, Value};
 
 use super::schema::{QueryState, TableFields};
 
@@ -96,6 +96,21 @@ pub fn merge_into_map_recursively(
                 }
             }
         }
+        // graph traversals are nested under their own keys, eg: `{ "->likes": { "->post": [...] } }`
+        Part::Graph(graph) => {
+            if parts.len() == 1 {
+                map.insert(graph.to_string(), return_type);
+            } else {
+                let next_map = map.entry(graph.to_string()).or_insert_with(|| kind!({}));
+
+                match next_map {
+                    Kind::Literal(Literal::Object(nested_fields)) => {
+                        merge_into_map_recursively(nested_fields, &parts[1..], return_type)?
+                    }
+                    _ => anyhow::bail!("Unsupported field return type: {:?}", next_map),
+                }
+            }
+        }
         Part::All => {
             if let Some(Part::Field(ident)) = parts.get(1) {
                 map.insert(ident.to_string(), kind!(Arr return_type));
@@ -121,3 +136,185 @@ pub fn is_double_optional(return_type: &Kind) -> bool {
         _ => false,
     }
 }
+
+/// Combine several return types into a single kind, flattening nested unions and
+/// removing duplicates while keeping the original order of the variants.
+pub fn union_kinds(kinds: Vec<Kind>) -> Kind {
+    let mut variants = Vec::new();
+
+    for kind in kinds {
+        match kind {
+            Kind::Either(nested) => {
+                for kind in nested {
+                    if !variants.contains(&kind) {
+                        variants.push(kind);
+                    }
+                }
+            }
+            kind => {
+                if !variants.contains(&kind) {
+                    variants.push(kind);
+                }
+            }
+        }
+    }
+
+    if variants.len() == 1 {
+        variants.pop().unwrap()
+    } else {
+        Kind::Either(variants)
+    }
+}
+
+/// The type of a value that can never be produced, eg: calling a function that always throws,
+/// which is an empty union so that it disappears when combined with any other type
+pub fn never() -> Kind {
+    Kind::Either(Vec::new())
+}
+
+/// Widen a literal kind to the kind of its value, eg: `"foo"` to `string`, used when constant
+/// values are merged together or assigned to a variable
+pub fn widen_literal(kind: &Kind) -> Kind {
+    match kind {
+        Kind::Literal(Literal::String(_)) => Kind::String,
+        Kind::Literal(Literal::Number(_)) => get_number_kind(kind),
+        Kind::Literal(Literal::Duration(_)) => Kind::Duration,
+        Kind::Option(inner) => kind!(Opt(widen_literal(inner))),
+        kind => kind.clone(),
+    }
+}
+
+/// Like [`union_kinds`], but any literals are widened when there is more than one variant,
+/// eg: `"ok" | "error"` becomes `string`, but `"ok"` on its own stays as it is
+pub fn widen_union(kinds: Vec<Kind>) -> Kind {
+    match union_kinds(kinds) {
+        Kind::Either(kinds) => union_kinds(kinds.iter().map(widen_literal).collect()),
+        kind => kind,
+    }
+}
+
+/// Wrap a kind in an `option`, unless it can already be `NONE`
+pub fn optional(kind: Kind) -> Kind {
+    match kind {
+        Kind::Option(_) | Kind::Any => kind,
+        kind => kind!(Opt(kind)),
+    }
+}
+
+/// Get the type of the elements yielded when iterating over an array-like kind
+pub fn get_array_element_type(kind: &Kind) -> Result<Kind, anyhow::Error> {
+    Ok(match kind {
+        Kind::Array(element, ..) | Kind::Set(element, ..) => *element.clone(),
+        Kind::Literal(Literal::Array(elements)) => union_kinds(elements.clone()),
+        // iterating over `NONE` simply yields no elements
+        Kind::Option(inner) => get_array_element_type(inner)?,
+        Kind::Either(kinds) => {
+            let mut element_types = Vec::new();
+            for kind in kinds {
+                element_types.push(get_array_element_type(kind)?);
+            }
+            union_kinds(element_types)
+        }
+        Kind::Any => Kind::Any,
+        _ => anyhow::bail!("Expected an array, got: {}", kind),
+    })
+}
+
+/// The numeric kind of a number, where a mix of numeric kinds can only be a `number`,
+/// eg: `int` stays an `int` but `int | float` is a `number`
+pub fn get_number_kind(kind: &Kind) -> Kind {
+    match kind {
+        Kind::Int | Kind::Float | Kind::Decimal => kind.clone(),
+        Kind::Literal(Literal::Number(Number::Int(_))) => Kind::Int,
+        Kind::Literal(Literal::Number(Number::Float(_))) => Kind::Float,
+        Kind::Literal(Literal::Number(Number::Decimal(_))) => Kind::Decimal,
+        Kind::Option(inner) => get_number_kind(inner),
+        Kind::Either(kinds) => {
+            let kinds = kinds.iter().map(get_number_kind).collect::<Vec<_>>();
+            match kinds.first() {
+                Some(first) if kinds.iter().all(|kind| kind == first) => first.clone(),
+                _ => Kind::Number,
+            }
+        }
+        _ => Kind::Number,
+    }
+}
+
+/// Check whether a value of type `from` can be used where `to` is expected.
+///
+/// Inferred types are often less precise than declared ones, so this is deliberately
+/// lenient: `any` is compatible with everything, numeric kinds are interchangeable,
+/// untyped records match any table and widened values match literals of the same kind.
+pub fn is_assignable(from: &Kind, to: &Kind) -> bool {
+    match (from, to) {
+        _ if from == to => true,
+        (Kind::Any, _) | (_, Kind::Any) => true,
+        // every variant of a union must fit
+        (Kind::Either(from_kinds), _) => from_kinds.iter().all(|from| is_assignable(from, to)),
+        (_, Kind::Either(to_kinds)) => to_kinds.iter().any(|to| is_assignable(from, to)),
+        (Kind::Option(from), Kind::Option(to)) => is_assignable(from, to),
+        (Kind::Null, Kind::Option(_)) => true,
+        (_, Kind::Option(to)) => is_assignable(from, to),
+        (
+            Kind::Int
+            | Kind::Float
+            | Kind::Decimal
+            | Kind::Number
+            | Kind::Literal(Literal::Number(_)),
+            Kind::Int | Kind::Float | Kind::Decimal | Kind::Number,
+        ) => true,
+        (Kind::Literal(Literal::String(_)), Kind::String) => true,
+        (Kind::Literal(Literal::Duration(_)), Kind::Duration) => true,
+        // constant values aren't always typed as literals, so they can't be ruled out
+        (
+            Kind::Int | Kind::Float | Kind::Decimal | Kind::Number,
+            Kind::Literal(Literal::Number(_)),
+        ) => true,
+        (Kind::String, Kind::Literal(Literal::String(_))) => true,
+        (Kind::Duration, Kind::Literal(Literal::Duration(_))) => true,
+        (Kind::Record(from_tables), Kind::Record(to_tables)) => {
+            from_tables.is_empty()
+                || to_tables.is_empty()
+                || from_tables.iter().all(|table| to_tables.contains(table))
+        }
+        (Kind::Array(from, ..) | Kind::Set(from, ..), Kind::Array(to, ..) | Kind::Set(to, ..)) => {
+            is_assignable(from, to)
+        }
+        (Kind::Literal(Literal::Array(from_kinds)), Kind::Array(to, ..) | Kind::Set(to, ..)) => {
+            from_kinds.iter().all(|from| is_assignable(from, to))
+        }
+        (Kind::Literal(Literal::Array(from_kinds)), Kind::Literal(Literal::Array(to_kinds))) => {
+            from_kinds.len() == to_kinds.len()
+                && from_kinds
+                    .iter()
+                    .zip(to_kinds)
+                    .all(|(from, to)| is_assignable(from, to))
+        }
+        (Kind::Literal(Literal::Object(_)), Kind::Object) => true,
+        (
+            Kind::Literal(Literal::Object(from_fields)),
+            Kind::Literal(Literal::Object(to_fields)),
+        ) => {
+            to_fields
+                .iter()
+                .all(|(key, to)| match from_fields.get(key) {
+                    Some(from) => is_assignable(from, to),
+                    // missing fields are fine as long as they are optional
+                    None => is_assignable(&Kind::Null, to),
+                })
+        }
+        _ => false,
+    }
+}
+
+/// Check whether an interpretation error comes from a construct that isn't supported yet,
+/// rather than a mistake such as an unknown field or parameter
+pub fn is_unsupported_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        let message = cause.to_string();
+
+        message.contains("supported")
+            || message.starts_with("TODO")
+            || message.starts_with("Unimplemented")
+    })
+}
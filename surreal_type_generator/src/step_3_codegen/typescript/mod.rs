@@ -1,10 +1,15 @@
 use crate::{kind, step_1_parse_sql::ViewParsed, utils::printing::indent, Kind, PrettyString};
-use surrealdb::sql::{Literal, Table};
+use surrealdb::sql::{Literal, Number, Table};
 
-use crate::step_2_interpret::SchemaState;
+use crate::step_2_interpret::{get_idiom_type, SchemaState};
 
 use super::TypeData;
 
+/// The default header of the generated file, which imports the `surrealdb` types that are
+/// referenced by the generated types
+pub const DEFAULT_HEADER: &str =
+    "import { type RecordId, type Geometry, type GeometryPoint, Surreal } from 'surrealdb'";
+
 pub fn format_comment(string: &str) -> String {
     let mut lines = Vec::new();
     lines.push("/**".into());
@@ -158,9 +163,24 @@ pub fn interpret_view_id_value_kind(
     let table_name = view.what.0.first().unwrap().to_string();
 
     match &view.groups {
-        Some(_groups) => {
-            // TODO: implement this
-            return Ok(Kind::Any);
+        // aggregate views are keyed by an array of the grouped values of each record,
+        // eg: `GROUP BY statement` gives ids like `statement_rating:[statement:1]`
+        Some(groups) => {
+            let fields = match state.schema.tables.get(&table_name) {
+                Some(table) => Some(table.compute_select_fields()?),
+                None => None,
+            };
+
+            let mut group_kinds = Vec::new();
+            for group in groups.iter() {
+                let kind = match &fields {
+                    Some(fields) => get_idiom_type(&group.0, fields)?,
+                    None => None,
+                };
+                group_kinds.push(kind.unwrap_or(Kind::Any));
+            }
+
+            Ok(Kind::Literal(Literal::Array(group_kinds)))
         }
         None => get_record_id_value_type(&table_name, state),
     }
@@ -176,6 +196,20 @@ pub fn get_record_id_value_type(table: &str, schema: &SchemaState) -> Result<Kin
     }
 }
 
+/// Keys that aren't valid identifiers, such as graph traversals (`->likes`), must be quoted
+fn format_object_key(key: &str) -> Result<String, anyhow::Error> {
+    let is_identifier = !key.is_empty()
+        && !key.starts_with(|c: char| c.is_ascii_digit())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+
+    match is_identifier {
+        true => Ok(key.to_string()),
+        false => Ok(serde_json::to_string(key)?),
+    }
+}
+
 fn generate_type_definition(
     return_type: &Kind,
     schema: &SchemaState,
@@ -192,10 +226,14 @@ fn generate_type_definition(
         Kind::Decimal => Ok("Decimal".to_string()),
         Kind::Bool => Ok("boolean".to_string()),
         Kind::Uuid => Ok("string".to_string()),
+        Kind::Bytes => Ok("ArrayBuffer".to_string()),
+        Kind::Point => Ok("GeometryPoint".to_string()),
+        Kind::Geometry(_) => Ok("Geometry".to_string()),
         Kind::Array(array, ..) => {
             let string = generate_type_definition(&**array, schema)?;
             Ok(format!("Array<{}>", string))
         }
+        Kind::Either(vec) if vec.is_empty() => Ok("never".to_string()),
         Kind::Either(vec) => {
             let mut output = String::new();
             output.push_str("(\n");
@@ -214,6 +252,8 @@ fn generate_type_definition(
             output.push_str("\n)");
             Ok(output)
         }
+        // a record link to any table
+        Kind::Record(tables) if tables.is_empty() => Ok("RecordId".to_string()),
         Kind::Record(tables) => {
             let mut output = String::new();
             output.push_str("(RecordId<");
@@ -242,7 +282,13 @@ fn generate_type_definition(
         // ========
         Kind::Literal(Literal::String(string)) => Ok(serde_json::to_string(&string)?),
         Kind::Literal(Literal::Duration(_duration)) => Ok("Duration".to_string()),
-        Kind::Literal(Literal::Number(number)) => Ok(number.to_string()),
+        Kind::Literal(Literal::Number(number)) => Ok(match number {
+            Number::Int(int) => int.to_string(),
+            // the default formatting adds a `f` suffix to floats
+            Number::Float(float) if float.is_finite() => float.to_string(),
+            Number::Float(_) => "number".to_string(),
+            _ => "Decimal".to_string(),
+        }),
         Kind::Literal(Literal::DiscriminatedObject(_, objects)) => {
             let kind = Kind::Either(
                 objects
@@ -258,6 +304,9 @@ fn generate_type_definition(
             let mut output = String::new();
             output.push_str("{\n");
 
+            // records of schemaless tables can contain any other keys
+            let is_open_object = schema.schema.is_schemaless_record(map);
+
             // sort alphabetically for deterministic output
             let mut map: Vec<(_, _)> = map.into_iter().collect();
             map.sort_by_key(|x| x.0.to_string());
@@ -267,7 +316,7 @@ fn generate_type_definition(
             for (key, value) in map {
                 key_string.push(format!(
                     "{}{}: {},\n",
-                    key,
+                    format_object_key(key)?,
                     match value {
                         Kind::Option(_) => "?",
                         _ => "",
@@ -283,6 +332,11 @@ fn generate_type_definition(
 
             output.push_str(&key_string);
             output.push_str("\n}");
+
+            if is_open_object {
+                output.push_str(" & { [k: string]: unknown }");
+            }
+
             Ok(output)
         }
         Kind::Literal(Literal::Array(array)) => {
@@ -26,6 +26,7 @@ pub trait PrettyString {
 impl PrettyString for Kind {
     fn pretty_string(&self) -> String {
         match self {
+            Kind::Record(tables) if tables.is_empty() => "record".yellow().to_string(),
             Kind::Record(tables) => format!(
                 "{}{}{}{}",
                 "record".yellow(),
@@ -70,6 +71,7 @@ impl PrettyString for Kind {
                 kind.pretty_string(),
                 ">".white()
             ),
+            Kind::Either(types) if types.is_empty() => "never".yellow().to_string(),
             Kind::Either(types) => types
                 .iter()
                 .map(|t| t.pretty_string())
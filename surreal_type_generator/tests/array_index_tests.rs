@@ -0,0 +1,152 @@
+mod common;
+
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, var_map, QueryResult};
+
+const SCHEMA: &str = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD tags ON user TYPE array<string>;
+DEFINE FIELD nicknames ON user TYPE option<array<string>>;
+DEFINE FIELD friends ON user TYPE array<record<user>>;
+DEFINE FIELD addresses ON user TYPE array<{ city: string, primary: bool }>;
+DEFINE FIELD scores ON user TYPE array<array<int>>;
+"#;
+
+#[test]
+fn array_index_access() -> anyhow::Result<()> {
+    let query = r#"
+SELECT
+    tags[0] AS first_tag,
+    tags[2] AS third_tag,
+    tags[$] AS last_tag,
+    nicknames[0] AS nickname,
+    scores[0] AS first_scores,
+    scores[*][0] AS first_of_each_score
+FROM user;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            first_tag: kind!(Opt(kind!(String))),
+            third_tag: kind!(Opt(kind!(String))),
+            last_tag: kind!(Opt(kind!(String))),
+            nickname: kind!(Opt(kind!(String))),
+            first_scores: kind!(Opt(kind!([kind!(Int)]))),
+            first_of_each_score: kind!([kind!(Opt(kind!(Int)))])
+        })])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn array_index_access_into_fields() -> anyhow::Result<()> {
+    let query = r#"
+SELECT
+    friends.name AS friend_names,
+    friends[0].name AS best_friend_name,
+    friends[$].tags[0] AS newest_friend_tag,
+    addresses.city AS cities,
+    addresses[0].city AS first_city
+FROM user;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            friend_names: kind!([kind!(String)]),
+            best_friend_name: kind!(Opt(kind!(String))),
+            newest_friend_tag: kind!(Opt(kind!(String))),
+            cities: kind!([kind!(String)]),
+            first_city: kind!(Opt(kind!(String)))
+        })])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn index_access_on_subqueries_and_params() -> anyhow::Result<()> {
+    let query = r#"
+LET $users = (SELECT * FROM user);
+RETURN $users[0].name;
+RETURN (SELECT name FROM user)[$];
+RETURN (SELECT name FROM user).name;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![
+            kind!(Null),
+            kind!(Opt(kind!(String))),
+            kind!(Opt(kind!({ name: kind!(String) }))),
+            kind!([kind!(String)])
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn array_filter_access() -> anyhow::Result<()> {
+    let query = r#"
+SELECT
+    addresses[WHERE primary = true] AS primary_addresses,
+    addresses[WHERE city = $city].city AS cities,
+    friends[WHERE name = $friend_name] AS friends_named,
+    tags[WHERE $this != $excluded_tag][0] AS first_tag
+FROM user;
+"#;
+
+    let QueryResult {
+        return_types,
+        variables,
+        ..
+    } = surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            primary_addresses: kind!([kind!({
+                city: kind!(String),
+                primary: kind!(Bool)
+            })]),
+            cities: kind!([kind!(String)]),
+            friends_named: kind!([kind!(Record ["user"])]),
+            first_tag: kind!(Opt(kind!(String)))
+        })])]
+    );
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            city: kind!(String),
+            friend_name: kind!(String),
+            excluded_tag: kind!(String)
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn array_filter_with_unknown_field() -> anyhow::Result<()> {
+    let query = r#"
+SELECT addresses[WHERE country = 'NZ'] AS addresses FROM user;
+"#;
+
+    common::assert_query_error(query, SCHEMA, "Field not found: country");
+
+    Ok(())
+}
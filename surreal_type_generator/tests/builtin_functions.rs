@@ -0,0 +1,235 @@
+mod common;
+
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{
+    kind, step_3_codegen::typescript::DEFAULT_HEADER, var_map, Kind, QueryResult,
+};
+use surrealdb::sql::Literal;
+
+const SCHEMA: &str = r#"
+DEFINE TABLE post SCHEMAFULL;
+DEFINE FIELD title ON post TYPE string;
+DEFINE FIELD tags ON post TYPE array<string>;
+DEFINE FIELD scores ON post TYPE array<array<int>>;
+"#;
+
+#[test]
+fn generic_array_function_return_types() -> anyhow::Result<()> {
+    let query = r#"
+SELECT
+    array::first(tags) AS first_tag,
+    array::flatten(scores) AS scores,
+    array::append(tags, 5) AS appended,
+    array::len(tags) AS count
+FROM post;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            first_tag: kind!(Opt(kind!(String))),
+            scores: kind!([kind!(Int)]),
+            appended: kind!([kind!(Either [kind!(String), kind!(Int)])]),
+            count: kind!(Int)
+        })])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn object_and_type_function_return_types() -> anyhow::Result<()> {
+    let query = r#"
+RETURN object::keys({ a: 1, b: "two" });
+RETURN type::thing("user", 1);
+RETURN string::split("a,b", ",");
+RETURN parse::url::port("https://example.com");
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![
+            kind!([kind!(Either [
+                Kind::Literal(Literal::String("a".into())),
+                Kind::Literal(Literal::String("b".into()))
+            ])]),
+            kind!(Record["user"]),
+            kind!([kind!(String)]),
+            kind!(Opt(kind!(Int)))
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn builtin_function_parameter_inference() -> anyhow::Result<()> {
+    let query = r#"
+RETURN string::len($name);
+RETURN array::first($values);
+RETURN time::format($at, "%Y");
+"#;
+
+    let QueryResult {
+        return_types,
+        variables,
+        ..
+    } = surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            name: kind!(String),
+            values: kind!([kind!(Any)]),
+            at: kind!(Datetime)
+        }
+    );
+
+    assert_eq_sorted!(return_types, vec![kind!(Int), kind!(Any), kind!(String)]);
+
+    Ok(())
+}
+
+#[test]
+fn variadic_function_arguments() -> anyhow::Result<()> {
+    let query = r#"
+RETURN string::concat("a", 1, true);
+RETURN string::join($separator, "a", 1, true);
+RETURN array::concat([1], $more, $most);
+"#;
+
+    let QueryResult {
+        return_types,
+        variables,
+        ..
+    } = surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            separator: kind!(String),
+            more: kind!([kind!(Any)]),
+            most: kind!([kind!(Any)])
+        }
+    );
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!(String), kind!(String), kind!([kind!(Any)])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn random_functions_with_bounds() -> anyhow::Result<()> {
+    let query = r#"
+RETURN rand::int();
+RETURN rand::int($min, $max);
+RETURN rand::float();
+RETURN rand::float(0, 1.5);
+"#;
+
+    let QueryResult {
+        return_types,
+        variables,
+        ..
+    } = surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            min: kind!(Opt(kind!(Int))),
+            max: kind!(Opt(kind!(Int)))
+        }
+    );
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!(Int), kind!(Int), kind!(Float), kind!(Float)]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn numeric_function_return_types() -> anyhow::Result<()> {
+    let query = r#"
+RETURN time::year(time::now());
+RETURN time::wday(time::now());
+RETURN time::unix(time::now());
+RETURN math::fixed(1.2345, 2);
+RETURN search::score(1);
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![
+            kind!(Int),
+            kind!(Int),
+            kind!(Int),
+            kind!(Float),
+            kind!(Float)
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn array_fill_replaces_elements() -> anyhow::Result<()> {
+    let query = r#"
+SELECT
+    array::fill(tags, 0) AS filled,
+    array::fill(tags, 0, 1, 2) AS partially_filled
+FROM post;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            filled: kind!([kind!(Int)]),
+            partially_filled: kind!([kind!(Either [kind!(String), kind!(Int)])])
+        })])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn geometry_and_bytes_typescript_types_are_imported() -> anyhow::Result<()> {
+    let query = r#"
+RETURN geo::hash::decode($hash);
+RETURN type::geometry($shape);
+RETURN encoding::base64::decode($data);
+"#;
+
+    let output = common::generate_typescript(SCHEMA, "geometry.surql", query)?;
+
+    for type_name in ["GeometryPoint", "Geometry", "ArrayBuffer"] {
+        assert!(output.contains(type_name), "missing `{}`", type_name);
+    }
+
+    // `ArrayBuffer` is built in to TypeScript, but the geometry types come from `surrealdb`
+    for type_name in ["GeometryPoint", "Geometry"] {
+        assert!(
+            DEFAULT_HEADER.contains(&format!("type {},", type_name)),
+            "`{}` is not imported by the default header",
+            type_name
+        );
+    }
+
+    Ok(())
+}
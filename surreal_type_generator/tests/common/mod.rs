@@ -0,0 +1,28 @@
+// each test file only uses some of these helpers
+#![allow(dead_code)]
+
+/// Generate the TypeScript output for a single query file
+pub fn generate_typescript(schema: &str, file_name: &str, query: &str) -> anyhow::Result<String> {
+    let state =
+        surreal_type_generator::step_2_interpret::interpret_schema(schema, Default::default())?;
+    let type_info = surreal_type_generator::step_3_codegen::generate_type_info(
+        file_name,
+        query,
+        std::sync::Arc::new(state),
+    )?;
+
+    surreal_type_generator::step_3_codegen::typescript::generate_typescript_output(&[type_info], "")
+}
+
+/// Assert that interpreting a query fails with an error containing `expected_error`
+pub fn assert_query_error(query: &str, schema: &str, expected_error: &str) {
+    match surreal_type_generator::step_3_codegen::query_to_return_type(query, schema) {
+        Ok(_) => panic!("expected an error for: {}", query),
+        Err(error) => assert!(
+            error.to_string().contains(expected_error),
+            "unexpected error for `{}`: {}",
+            query,
+            error
+        ),
+    }
+}
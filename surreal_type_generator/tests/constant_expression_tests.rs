@@ -1,5 +1,6 @@
 use pretty_assertions_sorted::assert_eq_sorted;
-use surreal_type_generator::{kind, step_3_codegen::QueryResult};
+use surreal_type_generator::{kind, step_3_codegen::QueryResult, Kind};
+use surrealdb::sql::Literal;
 
 #[test]
 fn constant_string() -> anyhow::Result<()> {
@@ -23,9 +24,9 @@ DEFINE TABLE foo SCHEMAFULL;
     assert_eq_sorted!(
         return_types,
         vec![kind!({
-            "123": kind!(Number),
+            "123": Kind::Literal(Literal::Number(123.into())),
             "false": kind!(Bool),
-            "foo": kind!(String),
+            "foo": Kind::Literal(Literal::String("foo".into())),
             "true": kind!(Bool),
             "NONE": kind!(Null),
             "NULL": kind!(Null)
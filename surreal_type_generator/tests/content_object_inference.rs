@@ -0,0 +1,120 @@
+mod common;
+
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, var_map, QueryResult};
+
+const SCHEMA: &str = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD email ON user TYPE option<string>;
+DEFINE FIELD tags ON user TYPE array<string>;
+DEFINE FIELD address ON user;
+DEFINE FIELD address.city ON user TYPE string;
+DEFINE FIELD updated_at ON user TYPE datetime VALUE time::now();
+"#;
+
+#[test]
+fn content_object_parameter_inference() -> anyhow::Result<()> {
+    let query = r#"
+CREATE user CONTENT {
+    name: $name,
+    email: $email,
+    tags: [$first_tag, "second"],
+    address: { city: $city },
+    updated_at: $ignored
+};
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            name: kind!(String),
+            email: kind!(Opt(kind!(String))),
+            first_tag: kind!(String),
+            city: kind!(String)
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn merge_and_insert_object_parameter_inference() -> anyhow::Result<()> {
+    let query = r#"
+UPDATE user MERGE { name: $new_name };
+UPSERT user:john CONTENT { address: { city: $city } };
+UPSERT user:jane MERGE { email: $email };
+INSERT INTO user { name: $name, tags: $tags };
+INSERT INTO user [{ name: $name }, { name: "jane", email: $other_email }];
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            name: kind!(String),
+            new_name: kind!(Opt(kind!(String))),
+            city: kind!(String),
+            email: kind!(Opt(kind!(String))),
+            other_email: kind!(Opt(kind!(String))),
+            tags: kind!([kind!(String)])
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn content_object_unknown_and_mismatched_fields() -> anyhow::Result<()> {
+    for (query, expected_error) in [
+        (
+            "CREATE user CONTENT { name: 'john', nickname: $nickname };",
+            "Field `nickname` does not exist on table `user`",
+        ),
+        (
+            "UPDATE user MERGE { address: { zip: $zip } };",
+            "Field `address.zip` does not exist on table `user`",
+        ),
+        (
+            "INSERT INTO user { name: 5 };",
+            "Cannot set field `name` of type `string` to `5`",
+        ),
+        (
+            "CREATE user CONTENT { name: time::now() };",
+            "Cannot set field `name` of type `string` to `time::now()`",
+        ),
+        (
+            "CREATE user SET name = math::abs(-5);",
+            "Cannot set field `name` of type `string` to `math::abs(-5)`",
+        ),
+    ] {
+        common::assert_query_error(query, SCHEMA, expected_error);
+    }
+
+    // schemaless tables accept any keys
+    let schema = r#"
+DEFINE TABLE post SCHEMALESS;
+DEFINE FIELD title ON post TYPE string;
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(
+            "CREATE post CONTENT { title: $title, extra: $extra };",
+            schema,
+        )?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            title: kind!(String),
+            extra: kind!(Any)
+        }
+    );
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, QueryResult};
+
+const SCHEMA: &str = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE TABLE team SCHEMAFULL;
+DEFINE FIELD title ON team TYPE string;
+DEFINE TABLE comment SCHEMAFULL;
+DEFINE FIELD text ON comment TYPE string;
+DEFINE FIELD author ON comment TYPE record<user>;
+DEFINE TABLE post SCHEMAFULL;
+DEFINE FIELD author ON post TYPE record<user>;
+DEFINE FIELD editors ON post TYPE array<record<user>>;
+DEFINE FIELD reviewer ON post TYPE option<record<user | team>>;
+DEFINE FIELD comments ON post TYPE array<record<comment>>;
+DEFINE FIELD owner ON post TYPE record<user> | record<user | team>;
+"#;
+
+fn user() -> surreal_type_generator::Kind {
+    kind!({
+        id: kind!(Record ["user"]),
+        name: kind!(String)
+    })
+}
+
+#[test]
+fn fetch_record_links() -> anyhow::Result<()> {
+    let query = r#"
+SELECT author, editors, reviewer FROM post FETCH author, editors, reviewer;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            author: user(),
+            editors: kind!([user()]),
+            reviewer: kind!(Opt(kind!(Either [
+                user(),
+                kind!({
+                    id: kind!(Record ["team"]),
+                    title: kind!(String)
+                })
+            ])))
+        })])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn fetch_nested_record_links() -> anyhow::Result<()> {
+    let query = r#"
+SELECT comments FROM post FETCH comments, comments.*.author;
+SELECT comments FROM post FETCH comments.author;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![
+            kind!([kind!({
+                comments: kind!([kind!({
+                    id: kind!(Record ["comment"]),
+                    text: kind!(String),
+                    author: user()
+                })])
+            })]),
+            // the record link part way through the path is fetched, but not the rest of it
+            kind!([kind!({
+                comments: kind!([kind!({
+                    id: kind!(Record ["comment"]),
+                    text: kind!(String),
+                    author: kind!(Record ["user"])
+                })])
+            })])
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn return_fetch() -> anyhow::Result<()> {
+    let query = r#"
+RETURN { author: user:john } FETCH author;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(return_types, vec![kind!({ author: user() })]);
+
+    Ok(())
+}
+
+#[test]
+fn fetch_union_of_record_links() -> anyhow::Result<()> {
+    let query = r#"
+SELECT owner FROM post FETCH owner;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    // the fetched records are merged into a single flat union without duplicates
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            owner: kind!(Either [
+                user(),
+                kind!({
+                    id: kind!(Record ["team"]),
+                    title: kind!(String)
+                })
+            ])
+        })])]
+    );
+
+    Ok(())
+}
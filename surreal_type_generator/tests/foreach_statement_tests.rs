@@ -0,0 +1,108 @@
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, var_map, QueryResult};
+
+#[test]
+fn foreach_binds_loop_variable_to_array_element() -> anyhow::Result<()> {
+    let query = r#"
+<array<record<user>>> $ids;
+
+FOR $id IN $ids {
+    UPSERT $id CONTENT $user;
+};
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+"#;
+
+    let QueryResult {
+        return_types,
+        variables,
+        ..
+    } = surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    let user_vars = kind!({
+        id: kind!(Opt (kind!(Record ["user"]))),
+        name: kind!(String)
+    });
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            ids: kind!([kind!(Record ["user"])]),
+            user: kind!(Either [user_vars.clone(), kind!([user_vars])])
+        }
+    );
+
+    assert_eq_sorted!(return_types, vec![kind!(Null)]);
+
+    Ok(())
+}
+
+#[test]
+fn foreach_over_range() -> anyhow::Result<()> {
+    let query = r#"
+FOR $i IN 0..10 {
+    LET $index = $i;
+};
+"#;
+    let schema = r#""#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(return_types, vec![kind!(Null)]);
+
+    Ok(())
+}
+
+#[test]
+fn foreach_over_non_array_errors() -> anyhow::Result<()> {
+    let query = r#"
+<string> $name;
+
+FOR $char IN $name {
+    CONTINUE;
+};
+"#;
+    let schema = r#""#;
+
+    assert!(surreal_type_generator::step_3_codegen::query_to_return_type(query, schema).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn foreach_in_field_value_clause() -> anyhow::Result<()> {
+    let query = r#"
+CREATE post CONTENT $post;
+"#;
+    let schema = r#"
+DEFINE TABLE post SCHEMAFULL;
+DEFINE FIELD tags ON post TYPE array<string> VALUE {
+    FOR $tag IN $value {
+        IF $tag = "" {
+            THROW "Tags cannot be empty";
+        };
+    };
+    $value
+};
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    let post_vars = kind!({
+        id: kind!(Opt (kind!(Record ["post"]))),
+        tags: kind!([kind!(String)])
+    });
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            post: kind!(Either [post_vars.clone(), kind!([post_vars])])
+        }
+    );
+
+    Ok(())
+}
@@ -1,5 +1,7 @@
+mod common;
+
 use pretty_assertions_sorted;
-use surreal_type_generator::QueryResult;
+use surreal_type_generator::{kind, var_map, QueryResult};
 
 #[test]
 fn custom_function_return_types() -> anyhow::Result<()> {
@@ -10,6 +12,83 @@ DEFINE FUNCTION fn::foo($bar: number) {
 
     let query = r#"
 RETURN fn::foo(9);
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    pretty_assertions_sorted::assert_eq_sorted!(return_types, vec![kind!(Int)]);
+
+    Ok(())
+}
+
+#[test]
+fn custom_function_literal_return_types() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE FUNCTION fn::inferred() {
+    RETURN "ok";
+};
+
+DEFINE FUNCTION fn::declared() -> "ok" | "error" {
+    RETURN "ok";
+};
+"#;
+
+    let query = r#"
+RETURN fn::inferred();
+RETURN fn::declared();
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    pretty_assertions_sorted::assert_eq_sorted!(
+        return_types,
+        vec![
+            // inferred return types are widened
+            kind!(String),
+            // but a declared literal return type is kept
+            kind!(Either[
+                surreal_type_generator::Kind::Literal(surrealdb::sql::Literal::String("ok".into())),
+                surreal_type_generator::Kind::Literal(surrealdb::sql::Literal::String(
+                    "error".into()
+                ))
+            ])
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn custom_function_with_multiple_statements() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+
+DEFINE FUNCTION fn::get_user($id: record<user>) {
+    IF !$id {
+        THROW "Missing user";
+    };
+
+    LET $user = (SELECT * FROM ONLY $id);
+
+    IF $user.name = "admin" {
+        RETURN "admin";
+    };
+
+    RETURN $user;
+};
+
+DEFINE FUNCTION fn::implicit_return($name: string) {
+    LET $greeting = $name;
+    $greeting
+};
+"#;
+
+    let query = r#"
+RETURN fn::get_user(user:john);
+RETURN fn::implicit_return("john");
 "#;
 
     let QueryResult { return_types, .. } =
@@ -17,8 +96,263 @@ RETURN fn::foo(9);
 
     pretty_assertions_sorted::assert_eq_sorted!(
         return_types,
-        vec![surreal_type_generator::Kind::Number]
+        vec![
+            kind!(Either[
+                kind!(String),
+                kind!({
+                    id: kind!(Record ["user"]),
+                    name: kind!(String)
+                })
+            ]),
+            kind!(String)
+        ]
     );
 
     Ok(())
 }
+
+#[test]
+fn custom_function_return_paths() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+
+DEFINE FUNCTION fn::branches($flag: bool) {
+    IF $flag {
+        RETURN 1;
+    } ELSE IF !$flag {
+        THROW "unreachable";
+    } ELSE {
+        RETURN "other";
+    };
+};
+
+DEFINE FUNCTION fn::find($names: array<string>) {
+    FOR $name IN $names {
+        IF $name = "john" {
+            RETURN $name;
+        };
+    };
+};
+
+DEFINE FUNCTION fn::upsert_user($name: string) {
+    RETURN UPSERT ONLY user:john SET name = $name;
+};
+
+DEFINE FUNCTION fn::always_throws() {
+    THROW "error";
+};
+"#;
+
+    let query = r#"
+RETURN fn::branches(true);
+RETURN fn::find(["john"]);
+RETURN fn::upsert_user("john");
+RETURN fn::always_throws();
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    pretty_assertions_sorted::assert_eq_sorted!(
+        return_types,
+        vec![
+            kind!(Either[kind!(Int), kind!(String)]),
+            kind!(Either[kind!(String), kind!(Null)]),
+            kind!({
+                id: kind!(Record ["user"]),
+                name: kind!(String)
+            }),
+            // a function that always throws never returns
+            surreal_type_generator::Kind::Either(vec![])
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn custom_function_that_always_throws() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE FUNCTION fn::fail($message: string) {
+    IF $message = "" {
+        THROW "Missing message";
+    } ELSE {
+        THROW $message;
+    };
+};
+
+DEFINE FUNCTION fn::declared_fail() -> string {
+    THROW "error";
+};
+"#;
+
+    let query = r#"
+RETURN fn::fail("error");
+RETURN fn::declared_fail();
+RETURN IF true { fn::fail("error") } ELSE { 5 };
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    pretty_assertions_sorted::assert_eq_sorted!(
+        return_types,
+        vec![
+            surreal_type_generator::Kind::Either(vec![]),
+            kind!(String),
+            // a branch that never returns doesn't add to the type
+            surreal_type_generator::Kind::Literal(surrealdb::sql::Literal::Number(5.into()))
+        ]
+    );
+
+    let output = common::generate_typescript(schema, "fail.surql", "RETURN fn::fail('error');")?;
+
+    assert!(output.contains("never"));
+
+    Ok(())
+}
+
+#[test]
+fn custom_function_declared_return_type() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+
+DEFINE FUNCTION fn::count_users($limit: int) -> int {
+    RETURN 5;
+};
+
+DEFINE FUNCTION fn::find_user($name: string) -> option<record<user>> {
+    RETURN (SELECT VALUE id FROM ONLY user WHERE name = $name LIMIT 1);
+};
+
+DEFINE FUNCTION fn::uninterpretable() -> string {
+    REMOVE TABLE temp;
+    RETURN "done";
+};
+"#;
+
+    let query = r#"
+RETURN fn::count_users(10);
+RETURN fn::find_user("john");
+RETURN fn::uninterpretable();
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    pretty_assertions_sorted::assert_eq_sorted!(
+        return_types,
+        vec![kind!(Int), kind!(Opt(kind!(Record["user"]))), kind!(String)]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn custom_function_declared_return_type_mismatch() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE FUNCTION fn::name() -> string {
+    RETURN 5;
+};
+"#;
+
+    let query = r#"
+RETURN fn::name();
+"#;
+
+    match surreal_type_generator::step_3_codegen::query_to_return_type(query, schema) {
+        Ok(_) => panic!("expected a return type mismatch"),
+        Err(error) => assert!(error.to_string().contains("declared to return `string`")),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn custom_function_declared_return_type_body_error() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+
+DEFINE FUNCTION fn::email($id: record<user>) -> string {
+    RETURN (SELECT VALUE email FROM ONLY $id);
+};
+"#;
+
+    let query = r#"
+RETURN fn::email(user:john);
+"#;
+
+    match surreal_type_generator::step_3_codegen::query_to_return_type(query, schema) {
+        Ok(_) => panic!("expected the body error to be surfaced"),
+        Err(error) => assert!(format!("{:#}", error).contains("Field not found: email")),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn custom_function_argument_inference() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+
+DEFINE FUNCTION fn::greet($user: record<user>, $greeting: option<string>) {
+    RETURN $greeting;
+};
+"#;
+
+    let query = r#"
+<string> $greeting;
+
+RETURN fn::greet($user);
+RETURN fn::greet(user:john, $greeting);
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    pretty_assertions_sorted::assert_eq_sorted!(
+        variables,
+        var_map! {
+            user: kind!(Record ["user"]),
+            greeting: kind!(String)
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn custom_function_argument_errors() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE FUNCTION fn::greet($name: string, $greeting: option<string>) {
+    RETURN $name;
+};
+"#;
+
+    for (query, expected_error) in [
+        (
+            "RETURN fn::greet();",
+            "expects 1 to 2 arguments, but 0 were given",
+        ),
+        (
+            "RETURN fn::greet('john', 'hello', 'extra');",
+            "expects 1 to 2 arguments, but 3 were given",
+        ),
+        (
+            "RETURN fn::greet(5);",
+            "Argument `$name` of function `fn::greet` expects `string`",
+        ),
+        (
+            "<int> $greeting; RETURN fn::greet('john', $greeting);",
+            "Argument `$greeting` of function `fn::greet` expects `option<string>`",
+        ),
+    ] {
+        common::assert_query_error(query, schema, expected_error);
+    }
+
+    Ok(())
+}
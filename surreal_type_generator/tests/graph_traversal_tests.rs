@@ -0,0 +1,126 @@
+mod common;
+
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, QueryResult};
+
+const SCHEMA: &str = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+
+DEFINE TABLE post SCHEMAFULL;
+DEFINE FIELD title ON post TYPE string;
+
+DEFINE TABLE likes SCHEMAFULL;
+DEFINE FIELD in ON likes TYPE record<user>;
+DEFINE FIELD out ON likes TYPE record<post>;
+DEFINE FIELD weight ON likes TYPE int;
+"#;
+
+#[test]
+fn graph_traversal_to_record_links() -> anyhow::Result<()> {
+    let query = r#"
+SELECT ->likes->post AS liked FROM user;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            liked: kind!([kind!(Record ["post"])])
+        })])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn graph_traversal_with_projection() -> anyhow::Result<()> {
+    let query = r#"
+SELECT
+    ->likes->post.* AS liked,
+    ->likes->post.title AS liked_titles,
+    <-likes<-user.name AS liked_by
+FROM user;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            liked: kind!([kind!({
+                id: kind!(Record ["post"]),
+                title: kind!(String)
+            })]),
+            liked_titles: kind!([kind!(String)]),
+            liked_by: kind!([kind!(String)])
+        })])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn graph_traversal_wildcard_uses_edge_types() -> anyhow::Result<()> {
+    let query = r#"
+SELECT ->likes->? AS liked, <-likes<-? AS liked_by FROM ONLY post:hello;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!({
+            liked: kind!([kind!(Record ["post"])]),
+            liked_by: kind!([kind!(Record ["user"])])
+        })]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn graph_traversal_from_parameter_with_condition() -> anyhow::Result<()> {
+    let query = r#"
+<record<user>> $user;
+
+RETURN $user->(likes WHERE weight > 1)->post;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(return_types, vec![kind!([kind!(Record["post"])])]);
+
+    Ok(())
+}
+
+#[test]
+fn graph_traversal_without_alias_is_nested() -> anyhow::Result<()> {
+    let query = r#"
+SELECT ->likes->post FROM ONLY user:john;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!({
+            "->likes": kind!({
+                "->post": kind!([kind!(Record ["post"])])
+            })
+        })]
+    );
+
+    let output = common::generate_typescript(SCHEMA, "liked_posts.surql", query)?;
+
+    assert!(output.contains("\"->likes\": {"));
+    assert!(output.contains("\"->post\": Array<"));
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, QueryResult};
+
+#[test]
+fn if_else_returns_union_of_branches() -> anyhow::Result<()> {
+    let query = r#"
+<bool> $flag;
+
+IF $flag {
+    RETURN SELECT * FROM ONLY user:john;
+} ELSE {
+    RETURN "not found";
+};
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!(Either [
+            kind!({
+                id: kind!(Record ["user"]),
+                name: kind!(String)
+            }),
+            kind!(String)
+        ])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn if_without_else_includes_none() -> anyhow::Result<()> {
+    let query = r#"
+<bool> $flag;
+
+IF $flag THEN "yes" END;
+"#;
+    let schema = r#""#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!(Either [kind!(String), kind!(Null)])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn if_else_if_branches_are_deduplicated() -> anyhow::Result<()> {
+    let query = r#"
+<int> $count;
+
+IF $count > 10 {
+    "many"
+} ELSE IF $count > 0 {
+    "some"
+} ELSE {
+    "none"
+};
+"#;
+    let schema = r#""#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(return_types, vec![kind!(String)]);
+
+    Ok(())
+}
+
+#[test]
+fn if_branch_let_bindings_are_scoped() -> anyhow::Result<()> {
+    let schema = r#""#;
+
+    let scoped_query = r#"
+<bool> $flag;
+
+IF $flag {
+    LET $name = "foo";
+    $name
+} ELSE {
+    NONE
+};
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(scoped_query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!(Either [kind!(String), kind!(Null)])]
+    );
+
+    let leaking_query = r#"
+<bool> $flag;
+
+IF $flag {
+    LET $name = "foo";
+};
+
+RETURN $name;
+"#;
+
+    assert!(
+        surreal_type_generator::step_3_codegen::query_to_return_type(leaking_query, schema)
+            .is_err()
+    );
+
+    Ok(())
+}
@@ -1,3 +1,5 @@
+mod common;
+
 use pretty_assertions_sorted::assert_eq_sorted;
 use surreal_type_generator::{kind, var_map, QueryResult};
 
@@ -66,3 +68,154 @@ DEFINE FIELD name ON user TYPE string;
 
     Ok(())
 }
+
+#[test]
+fn insert_on_duplicate_key_update() -> anyhow::Result<()> {
+    let query = r#"
+INSERT INTO user { name: $name, visits: 1 }
+    ON DUPLICATE KEY UPDATE visits += 1, name = $input.name, nickname = $nickname
+    RETURN BEFORE;
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD nickname ON user TYPE option<string>;
+DEFINE FIELD visits ON user TYPE int;
+"#;
+
+    let QueryResult {
+        return_types,
+        variables,
+        ..
+    } = surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            name: kind!(String),
+            nickname: kind!(Opt(kind!(String)))
+        }
+    );
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!(Either [
+            kind!({
+                id: kind!(Record ["user"]),
+                name: kind!(String),
+                nickname: kind!(Opt(kind!(String))),
+                visits: kind!(Int)
+            }),
+            kind!(Null)
+        ])])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn insert_on_duplicate_key_update_checks_fields() -> anyhow::Result<()> {
+    let query = r#"
+INSERT INTO user { name: "john" } ON DUPLICATE KEY UPDATE name = 5;
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+"#;
+
+    common::assert_query_error(
+        query,
+        schema,
+        "Cannot set field `name` of type `string` to `5`",
+    );
+
+    Ok(())
+}
+
+#[test]
+fn insert_values_parameter_inference() -> anyhow::Result<()> {
+    let query = r#"
+INSERT INTO user (name, email, age) VALUES ($name, $email, 42), ("jane", NONE, $age);
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD email ON user TYPE option<string>;
+DEFINE FIELD age ON user TYPE int DEFAULT 18;
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            name: kind!(String),
+            email: kind!(Opt(kind!(String))),
+            age: kind!(Opt(kind!(Int)))
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn insert_relation_values() -> anyhow::Result<()> {
+    let query = r#"
+INSERT RELATION INTO likes (in, out, weight) VALUES ($user, $post, $weight);
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE TABLE post SCHEMAFULL;
+DEFINE TABLE likes TYPE RELATION IN user OUT post SCHEMAFULL;
+DEFINE FIELD weight ON likes TYPE float;
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            user: kind!(Record ["user"]),
+            post: kind!(Record ["post"]),
+            weight: kind!(Float)
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn insert_missing_required_columns() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD email ON user TYPE option<string>;
+DEFINE TABLE post SCHEMAFULL;
+DEFINE TABLE likes TYPE RELATION IN user OUT post SCHEMAFULL;
+"#;
+
+    for (query, expected_error) in [
+        (
+            "INSERT INTO user (email) VALUES ($email);",
+            "Missing required field `name` when inserting into `user`",
+        ),
+        (
+            "INSERT RELATION INTO likes (in) VALUES ($user);",
+            "Missing required field `out` when inserting into `likes`",
+        ),
+        (
+            "INSERT RELATION INTO likes { in: $user };",
+            "Missing required field `out` when inserting into `likes`",
+        ),
+        (
+            "INSERT RELATION INTO user { in: $user, out: $post };",
+            "Table `user` is not a relation table",
+        ),
+    ] {
+        common::assert_query_error(query, schema, expected_error);
+    }
+
+    Ok(())
+}
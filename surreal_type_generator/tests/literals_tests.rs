@@ -1,3 +1,5 @@
+mod common;
+
 use pretty_assertions_sorted;
 use surreal_type_generator::{kind, Kind, Literal, QueryResult};
 use surrealdb::sql::Duration;
@@ -58,7 +60,7 @@ SELECT [] as foo, [1, 2, 3] as num_list FROM baz;
         return_types,
         vec![kind!([kind!({
             foo: kind!([kind!(Null)]),
-            num_list: kind!([kind!(Number)])
+            num_list: kind!([kind!(Int)])
         })])]
     );
 }
@@ -116,3 +118,95 @@ SELECT foo FROM baz;
         })])]
     );
 }
+
+#[test]
+fn constant_values_are_literals() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE baz SCHEMAFULL;
+"#;
+
+    let query = r#"
+RETURN { status: "ok", code: 200 };
+RETURN IF <bool> $ok {
+    { status: "ok", code: 200 }
+} ELSE {
+    { status: "error", message: "failed" }
+};
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    pretty_assertions_sorted::assert_eq_sorted!(
+        return_types,
+        vec![
+            kind!({
+                status: Kind::Literal(Literal::String("ok".into())),
+                code: Kind::Literal(Literal::Number(200.into()))
+            }),
+            // literals nested in objects survive being merged, so the union stays discriminated
+            kind!(Either [
+                kind!({
+                    status: Kind::Literal(Literal::String("ok".into())),
+                    code: Kind::Literal(Literal::Number(200.into()))
+                }),
+                kind!({
+                    status: Kind::Literal(Literal::String("error".into())),
+                    message: Kind::Literal(Literal::String("failed".into()))
+                })
+            ])
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn literals_are_widened_when_merged_or_assigned() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE baz SCHEMAFULL;
+"#;
+
+    let query = r#"
+LET $status = "ok";
+RETURN $status;
+RETURN IF <bool> $ok { "ok" } ELSE { "error" };
+RETURN IF <bool> $ok { "ok" } ELSE { "ok" };
+RETURN [1.5, 2.5];
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    pretty_assertions_sorted::assert_eq_sorted!(
+        return_types,
+        vec![
+            kind!(Null),
+            kind!(String),
+            kind!(String),
+            Kind::Literal(Literal::String("ok".into())),
+            kind!([kind!(Float)])
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn literal_typescript_output() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE baz SCHEMAFULL;
+"#;
+
+    let query = r#"
+RETURN { status: "ok", code: 200, ratio: 0.5 };
+"#;
+
+    let output = common::generate_typescript(schema, "status.surql", query)?;
+
+    assert!(output.contains("status: \"ok\""));
+    assert!(output.contains("code: 200"));
+    assert!(output.contains("ratio: 0.5"));
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use pretty_assertions_sorted::assert_eq_sorted;
-use surreal_type_generator::{kind, QueryResult};
+use surreal_type_generator::{kind, Kind, QueryResult};
+use surrealdb::sql::Literal;
 
 #[test]
 fn can_interpret_object() -> Result<(), anyhow::Error> {
@@ -24,10 +25,10 @@ RETURN {
         return_types,
         vec![kind!({
             foo: kind!({
-                bar: kind!(Number),
-                baz: kind!(Number)
+                bar: Kind::Literal(Literal::Number(1.into())),
+                baz: Kind::Literal(Literal::Number(2.into()))
             }),
-            qux: kind!(Number)
+            qux: Kind::Literal(Literal::Number(3.into()))
         })]
     );
 
@@ -0,0 +1,101 @@
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, var_map, Kind, QueryResult};
+use surrealdb::sql::Literal;
+
+fn literal(string: &str) -> Kind {
+    Kind::Literal(Literal::String(string.into()))
+}
+
+#[test]
+fn patch_parameter_inference() -> anyhow::Result<()> {
+    let query = r#"
+UPDATE user:john PATCH $ops;
+UPSERT user:jane PATCH $upsert_ops;
+"#;
+
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD nickname ON user TYPE option<string>;
+DEFINE FIELD created_at ON user TYPE datetime DEFAULT time::now() READONLY;
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    let write_ops = kind!(Either [literal("add"), literal("replace"), literal("test")]);
+    let ops = kind!([kind!(Either [
+        kind!({
+            op: write_ops.clone(),
+            path: literal("/name"),
+            value: kind!(String)
+        }),
+        kind!({
+            op: write_ops,
+            path: literal("/nickname"),
+            value: kind!(Opt(kind!(String)))
+        }),
+        kind!({
+            op: literal("remove"),
+            path: literal("/nickname")
+        }),
+        kind!({
+            op: kind!(Either [literal("copy"), literal("move")]),
+            from: kind!(Either [literal("/name"), literal("/nickname")]),
+            path: kind!(Either [literal("/name"), literal("/nickname")])
+        })
+    ])]);
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            ops: ops.clone(),
+            upsert_ops: ops
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn patch_nested_field_paths() -> anyhow::Result<()> {
+    let query = r#"
+UPDATE user PATCH $ops;
+"#;
+
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD address ON user;
+DEFINE FIELD address.city ON user TYPE string;
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    let write_ops = kind!(Either [literal("add"), literal("replace"), literal("test")]);
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            ops: kind!([kind!(Either [
+                kind!({
+                    op: write_ops.clone(),
+                    path: literal("/address"),
+                    value: kind!({ city: kind!(String) })
+                }),
+                kind!({
+                    op: write_ops,
+                    path: literal("/address/city"),
+                    value: kind!(String)
+                }),
+                kind!({
+                    op: kind!(Either [literal("copy"), literal("move")]),
+                    from: kind!(Either [literal("/address"), literal("/address/city")]),
+                    path: kind!(Either [literal("/address"), literal("/address/city")])
+                })
+            ])])
+        }
+    );
+
+    Ok(())
+}
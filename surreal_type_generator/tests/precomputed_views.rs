@@ -1,5 +1,6 @@
 use pretty_assertions_sorted::assert_eq_sorted;
-use surreal_type_generator::{kind, QueryResult};
+use surreal_type_generator::{kind, Kind, QueryResult};
+use surrealdb::sql::Literal;
 
 #[test]
 fn precomputed_views() -> anyhow::Result<()> {
@@ -54,12 +55,12 @@ SELECT * FROM baz;
         vec![
             kind!([kind!({
                 num: kind!(Number),
-                five: kind!(Number)
+                five: Kind::Literal(Literal::Number(5.into()))
             })]),
             kind!([kind!({
                 id: kind!(Record ["baz"]),
                 num: kind!(Number),
-                five: kind!(Number),
+                five: Kind::Literal(Literal::Number(5.into())),
                 beep: kind!(Number)
             })])
         ]
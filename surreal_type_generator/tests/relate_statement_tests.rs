@@ -0,0 +1,195 @@
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, var_map, QueryResult};
+
+#[test]
+fn relate_statement_returns_edge_fields() -> anyhow::Result<()> {
+    let query = r#"
+RELATE user:john->likes->post:hello SET created_at = time::now();
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE TABLE post SCHEMAFULL;
+DEFINE TABLE likes SCHEMAFULL;
+DEFINE FIELD created_at ON likes TYPE datetime;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            id: kind!(Record ["likes"]),
+            in: kind!(Record ["user"]),
+            out: kind!(Record ["post"]),
+            created_at: kind!(Datetime)
+        })])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn relate_only_with_params() -> anyhow::Result<()> {
+    let query = r#"
+<record<user>> $user;
+<record<post>> $post;
+
+RELATE ONLY $user->likes->$post;
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE TABLE post SCHEMAFULL;
+DEFINE TABLE likes SCHEMAFULL;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!({
+            id: kind!(Record ["likes"]),
+            in: kind!(Record ["user"]),
+            out: kind!(Record ["post"])
+        })]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn relate_return_variants() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE TABLE post SCHEMAFULL;
+DEFINE TABLE likes SCHEMAFULL;
+DEFINE FIELD weight ON likes TYPE int;
+"#;
+
+    for (query, expected) in [
+        (
+            "RELATE ONLY user:john->likes->post:hello RETURN NONE;",
+            kind!(Null),
+        ),
+        (
+            "RELATE ONLY user:john->likes->post:hello RETURN BEFORE;",
+            kind!(Null),
+        ),
+        (
+            "RELATE ONLY user:john->likes->post:hello RETURN out, weight;",
+            kind!({
+                out: kind!(Record ["post"]),
+                weight: kind!(Int)
+            }),
+        ),
+        (
+            "RELATE ONLY user:john->likes->post:hello RETURN VALUE $after.in;",
+            kind!(Record["user"]),
+        ),
+    ] {
+        let QueryResult { return_types, .. } =
+            surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+        assert_eq_sorted!(return_types, vec![expected]);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn relate_content_parameter_inference() -> anyhow::Result<()> {
+    let query = r#"
+RELATE ONLY user:john->likes->post:hello CONTENT $like;
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE TABLE post SCHEMAFULL;
+DEFINE TABLE likes SCHEMAFULL;
+DEFINE FIELD weight ON likes TYPE int;
+DEFINE FIELD created_at ON likes TYPE datetime VALUE time::now();
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            like: kind!({
+                id: kind!(Opt (kind!(Record ["likes"]))),
+                weight: kind!(Int)
+            })
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn relate_subquery() -> anyhow::Result<()> {
+    let query = r#"
+RETURN (RELATE ONLY (SELECT * FROM ONLY user:john)->likes->post:hello);
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE TABLE post SCHEMAFULL;
+DEFINE TABLE likes SCHEMAFULL;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!({
+            id: kind!(Record ["likes"]),
+            in: kind!(Record ["user"]),
+            out: kind!(Record ["post"])
+        })]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn relate_content_and_set_on_relation_table() -> anyhow::Result<()> {
+    let query = r#"
+RELATE ONLY user:john->likes->post:hello CONTENT { weight: 1 };
+RELATE ONLY user:john->likes->post:hello SET weight = 2;
+RELATE ONLY user:john->likes->post:hello CONTENT $like;
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE TABLE post SCHEMAFULL;
+DEFINE TABLE likes TYPE RELATION IN user OUT post SCHEMAFULL;
+DEFINE FIELD weight ON likes TYPE int;
+"#;
+
+    let QueryResult {
+        return_types,
+        variables,
+        ..
+    } = surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    let like = kind!({
+        id: kind!(Record ["likes"]),
+        in: kind!(Record ["user"]),
+        out: kind!(Record ["post"]),
+        weight: kind!(Int)
+    });
+
+    assert_eq_sorted!(return_types, vec![like.clone(), like.clone(), like]);
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            like: kind!({
+                id: kind!(Opt (kind!(Record ["likes"]))),
+                weight: kind!(Int)
+            })
+        }
+    );
+
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, step_1_parse_sql::parse_schema, QueryResult};
+use surrealdb::sql::TableType;
+
+const SCHEMA: &str = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE TABLE post SCHEMAFULL;
+DEFINE TABLE likes TYPE RELATION IN user OUT post SCHEMAFULL;
+DEFINE FIELD weight ON likes TYPE int;
+DEFINE TABLE follows TYPE RELATION SCHEMAFULL;
+"#;
+
+#[test]
+fn relation_tables_have_typed_in_and_out_fields() -> anyhow::Result<()> {
+    let schema = parse_schema(SCHEMA)?;
+
+    let likes = schema.tables.get("likes").unwrap();
+    assert!(matches!(likes.table_type, TableType::Relation(_)));
+    assert_eq_sorted!(likes.fields["in"].return_type, kind!(Record["user"]));
+    assert_eq_sorted!(likes.fields["out"].return_type, kind!(Record["post"]));
+    assert!(likes.fields["in"].readonly);
+    assert!(!likes.fields["out"].is_optional);
+
+    let user = schema.tables.get("user").unwrap();
+    assert!(!matches!(user.table_type, TableType::Relation(_)));
+    assert!(!user.fields.contains_key("in"));
+
+    Ok(())
+}
+
+#[test]
+fn select_from_relation_table() -> anyhow::Result<()> {
+    let query = r#"
+SELECT * FROM likes;
+SELECT * FROM follows;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![
+            kind!([kind!({
+                id: kind!(Record ["likes"]),
+                in: kind!(Record ["user"]),
+                out: kind!(Record ["post"]),
+                weight: kind!(Int)
+            })]),
+            kind!([kind!({
+                id: kind!(Record ["follows"]),
+                in: surreal_type_generator::Kind::Record(vec![]),
+                out: surreal_type_generator::Kind::Record(vec![])
+            })])
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn graph_wildcard_uses_relation_types() -> anyhow::Result<()> {
+    let query = r#"
+SELECT ->likes->? AS liked FROM user;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            liked: kind!([kind!(Record ["post"])])
+        })])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn relate_into_relation_table() -> anyhow::Result<()> {
+    let query = r#"
+<record<user>> $user;
+
+RELATE ONLY $user->likes->post:hello CONTENT $like;
+"#;
+
+    let QueryResult {
+        return_types,
+        variables,
+        ..
+    } = surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables["like"],
+        kind!({
+            id: kind!(Opt (kind!(Record ["likes"]))),
+            weight: kind!(Int)
+        })
+    );
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!({
+            id: kind!(Record ["likes"]),
+            in: kind!(Record ["user"]),
+            out: kind!(Record ["post"]),
+            weight: kind!(Int)
+        })]
+    );
+
+    Ok(())
+}
@@ -1,5 +1,16 @@
+mod common;
+
 use pretty_assertions_sorted::assert_eq_sorted;
-use surreal_type_generator::{Kind, QueryResult};
+use surreal_type_generator::{kind, Kind, QueryResult};
+use surrealdb::sql::Literal;
+
+fn literal_string(string: &str) -> Kind {
+    Kind::Literal(Literal::String(string.into()))
+}
+
+fn literal_number(number: i64) -> Kind {
+    Kind::Literal(Literal::Number(number.into()))
+}
 
 #[test]
 fn return_and_expressions() -> anyhow::Result<()> {
@@ -38,9 +49,9 @@ DEFINE TABLE placeholder SCHEMAFULL;
     assert_eq_sorted!(
         return_types,
         vec![
-            Kind::String,
-            Kind::Number,
-            Kind::Number,
+            literal_string("bar"),
+            Kind::Float,
+            literal_number(-1),
             Kind::Bool,
             Kind::Bool,
             Kind::Bool,
@@ -53,47 +64,192 @@ DEFINE TABLE placeholder SCHEMAFULL;
             Kind::Bool,
             Kind::Bool,
             Kind::Bool,
-            Kind::Number
+            literal_number(1)
         ]
     );
 
     Ok(())
 }
 
-/*
--- arithmetic expressions
+#[test]
+fn arithmetic_expressions() -> anyhow::Result<()> {
+    let query = r#"
 RETURN 1 + 1;
 RETURN 1 - 1;
 RETURN 1 * 1;
 RETURN 1 ** 1;
 RETURN 1 / 1;
 RETURN 1 % 2;
-*/
+RETURN -<int> $int;
+
+-- numeric promotion
+RETURN <int> $int + <int> $int;
+RETURN <int> $int * <float> $float;
+RETURN <int> $int - <decimal> $decimal;
+RETURN <int> $int / <int> $int;
+RETURN <float> $float / <int> $int;
+RETURN <int> $int ** <int> $int;
+RETURN <option<int>> $maybe_int + <int> $int;
+
+-- datetimes, durations, strings and arrays
+RETURN time::now() + 1h;
+RETURN time::now() - <datetime> $datetime;
+RETURN 1h + 1m;
+RETURN "foo" + "bar";
+RETURN <array<string>> $strings + <array<int>> $ints;
+"#;
+    let schema = r#"
+DEFINE TABLE placeholder SCHEMAFULL;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![
+            Kind::Int,
+            Kind::Int,
+            Kind::Int,
+            Kind::Int,
+            Kind::Number,
+            Kind::Int,
+            Kind::Int,
+            Kind::Int,
+            Kind::Float,
+            Kind::Decimal,
+            Kind::Number,
+            Kind::Float,
+            Kind::Int,
+            Kind::Int,
+            Kind::Datetime,
+            Kind::Duration,
+            Kind::Duration,
+            Kind::String,
+            kind!([kind!(Either[kind!(String), kind!(Int)])]),
+        ]
+    );
+
+    Ok(())
+}
 
-/*
--- shortcircuiting expressions
+#[test]
+fn arithmetic_on_fields() -> anyhow::Result<()> {
+    let query = r#"
+SELECT
+    age + 1 AS next_age,
+    -score AS negative_score,
+    created_at + 1d AS tomorrow,
+    tags + ["new"] AS tags
+FROM user;
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD age ON user TYPE int;
+DEFINE FIELD score ON user TYPE float;
+DEFINE FIELD created_at ON user TYPE datetime;
+DEFINE FIELD tags ON user TYPE array<string>;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            next_age: kind!(Int),
+            negative_score: kind!(Float),
+            tomorrow: kind!(Datetime),
+            tags: kind!([kind!(String)])
+        })])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn invalid_arithmetic_expression() -> anyhow::Result<()> {
+    let query = r#"
+RETURN "foo" * <datetime> $datetime;
+"#;
+    let schema = r#"
+DEFINE TABLE placeholder SCHEMAFULL;
+"#;
+
+    common::assert_query_error(query, schema, "Cannot apply `*` to `'foo'` and `datetime`");
+
+    Ok(())
+}
+
+#[test]
+fn shortcircuiting_expressions() -> anyhow::Result<()> {
+    let query = r#"
 RETURN 1 ?: 1;
 RETURN 1 ?: null;
 RETURN null ?? 1;
 RETURN 1 ?? 1;
-*/
-
-// -- ## TODOS ##
-// -- !~
-// -- ~
-// -- ?~
-// -- *~
-// -- CONTAINS
-// -- CONTAINSNOT
-// -- CONTAINSALL
-// -- CONTAINSANY
-// -- CONTAINSNONE
-// -- INSIDE
-// -- NOTINSIDE
-// -- ALLINSIDE
-// -- ANYINSIDE
-// -- NONEINSIDE
-// -- OUTSIDE
-// -- INTERSECTS
-// -- @@ (MATCHES)
-// -- <|4|> #KNN
+RETURN (<option<string>> $name) ?? "anonymous";
+RETURN (<option<string>> $name) ?? 1;
+"#;
+    let schema = r#"
+DEFINE TABLE placeholder SCHEMAFULL;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![
+            literal_number(1),
+            kind!(Either[kind!(Int), kind!(Null)]),
+            literal_number(1),
+            literal_number(1),
+            Kind::String,
+            kind!(Either[kind!(String), kind!(Int)]),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn comparison_and_containment_expressions() -> anyhow::Result<()> {
+    let query = r#"
+RETURN "foo" !~ "bar";
+RETURN "foo" ~ "bar";
+RETURN ["foo"] ?~ "bar";
+RETURN ["foo"] *~ "bar";
+RETURN [1, 2] CONTAINS 1;
+RETURN [1, 2] CONTAINSNOT 1;
+RETURN [1, 2] CONTAINSALL [1];
+RETURN [1, 2] CONTAINSANY [1];
+RETURN [1, 2] CONTAINSNONE [1];
+RETURN 1 INSIDE [1, 2];
+RETURN 1 NOTINSIDE [1, 2];
+RETURN [1] ALLINSIDE [1, 2];
+RETURN [1] ANYINSIDE [1, 2];
+RETURN [1] NONEINSIDE [1, 2];
+RETURN 1 IN [1, 2];
+RETURN 1 NOT IN [1, 2];
+RETURN [1, 2] ?= 1;
+RETURN [1, 2] *= 1;
+RETURN (SELECT VALUE name FROM user WHERE name @@ "foo");
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE ANALYZER simple TOKENIZERS blank;
+DEFINE INDEX name_search ON user FIELDS name SEARCH ANALYZER simple BM25;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    let mut expected = vec![Kind::Bool; 18];
+    expected.push(kind!([kind!(String)]));
+
+    assert_eq_sorted!(return_types, expected);
+
+    Ok(())
+}
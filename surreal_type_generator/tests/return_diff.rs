@@ -0,0 +1,103 @@
+mod common;
+
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, Kind, QueryResult};
+use surrealdb::sql::Literal;
+
+const SCHEMA: &str = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD age ON user TYPE option<int>;
+"#;
+
+fn literal(string: &str) -> Kind {
+    Kind::Literal(Literal::String(string.into()))
+}
+
+fn user() -> Kind {
+    kind!({
+        id: kind!(Record ["user"]),
+        name: kind!(String),
+        age: kind!(Opt(kind!(Int)))
+    })
+}
+
+#[test]
+fn create_and_delete_return_diff() -> anyhow::Result<()> {
+    let query = r#"
+CREATE ONLY user CONTENT { name: "john" } RETURN DIFF;
+DELETE user RETURN DIFF;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![
+            kind!([kind!({
+                op: literal("replace"),
+                path: literal("/"),
+                value: user()
+            })]),
+            kind!([kind!([kind!({
+                op: literal("replace"),
+                path: literal("/"),
+                value: kind!(Null)
+            })])])
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn update_return_diff() -> anyhow::Result<()> {
+    let query = r#"
+UPDATE ONLY user:john SET name = "jane" RETURN DIFF;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!(Either [
+            kind!({
+                op: kind!(Either [literal("add"), literal("replace")]),
+                path: literal("/age"),
+                value: kind!(Int)
+            }),
+            kind!({
+                op: kind!(Either [literal("add"), literal("replace")]),
+                path: literal("/name"),
+                value: kind!(String)
+            }),
+            kind!({
+                op: literal("change"),
+                path: literal("/name"),
+                value: kind!(String)
+            }),
+            kind!({
+                op: literal("remove"),
+                path: literal("/age")
+            })
+        ])])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn return_diff_typescript_output() -> anyhow::Result<()> {
+    let query = r#"
+INSERT INTO user { name: "john" } RETURN DIFF;
+"#;
+
+    let output = common::generate_typescript(SCHEMA, "insert_user.surql", query)?;
+
+    assert!(output.contains("op: \"replace\""));
+    assert!(output.contains("path: \"/\""));
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, QueryResult};
+
+#[test]
+fn define_param_is_typed_global() -> anyhow::Result<()> {
+    let query = r#"
+RETURN $app_name;
+RETURN $limits;
+SELECT * FROM user LIMIT $max_users;
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+
+DEFINE PARAM $app_name VALUE "surreal-codegen";
+DEFINE PARAM $max_users VALUE 100;
+DEFINE PARAM $limits VALUE { users: $max_users, names: ["a", "b"] };
+"#;
+
+    let QueryResult {
+        return_types,
+        variables,
+        ..
+    } = surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![
+            kind!(String),
+            kind!({
+                users: kind!(Int),
+                names: kind!([kind!(String)])
+            }),
+            kind!([kind!({
+                id: kind!(Record ["user"]),
+                name: kind!(String)
+            })])
+        ]
+    );
+
+    // globals are not required query variables
+    assert!(variables.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn define_param_with_query_value() -> anyhow::Result<()> {
+    let query = r#"
+RETURN $admin;
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+
+DEFINE PARAM $admin VALUE (SELECT name FROM ONLY user:admin);
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!({
+            name: kind!(String)
+        })]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn global_casts_override_define_param() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE PARAM $max_users VALUE 100;
+"#;
+
+    let globals = surreal_type_generator::step_1_parse_sql::parse_value_casts("<int> $max_users;")?;
+    let state = surreal_type_generator::step_2_interpret::interpret_schema(schema, globals)?;
+    let type_info = surreal_type_generator::step_3_codegen::generate_type_info(
+        "max_users.surql",
+        "RETURN $max_users;",
+        std::sync::Arc::new(state),
+    )?;
+
+    assert_eq_sorted!(type_info.return_type, vec![kind!(Int)]);
+
+    Ok(())
+}
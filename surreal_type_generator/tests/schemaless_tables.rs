@@ -0,0 +1,91 @@
+mod common;
+
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, var_map, QueryResult};
+
+const SCHEMA: &str = r#"
+DEFINE TABLE user SCHEMALESS;
+DEFINE FIELD name ON user TYPE string;
+"#;
+
+#[test]
+fn select_from_schemaless_table_is_open() -> anyhow::Result<()> {
+    let query = r#"
+SELECT * FROM user;
+SELECT name, nickname FROM ONLY user:john;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![
+            kind!([kind!({
+                id: kind!(Record ["user"]),
+                name: kind!(String)
+            })]),
+            kind!({
+                name: kind!(String),
+                nickname: kind!(Any)
+            })
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn create_on_schemaless_table_accepts_extra_keys() -> anyhow::Result<()> {
+    let query = r#"
+CREATE user CONTENT $user;
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    let user_vars = kind!({
+        id: kind!(Opt (kind!(Record ["user"]))),
+        name: kind!(String)
+    });
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            user: kind!(Either [user_vars.clone(), kind!([user_vars])])
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn schemaless_typescript_output_has_index_signature() -> anyhow::Result<()> {
+    let query = r#"
+SELECT * FROM ONLY user:john;
+"#;
+
+    let output = common::generate_typescript(SCHEMA, "get_user.surql", query)?;
+
+    assert!(output.contains("} & { [k: string]: unknown }"));
+    assert!(!output.contains("\"[k: string]\""));
+
+    Ok(())
+}
+
+#[test]
+fn update_on_schemaless_table_accepts_extra_keys() -> anyhow::Result<()> {
+    let query = r#"
+UPDATE ONLY user:john MERGE $user;
+"#;
+
+    let output = common::generate_typescript(SCHEMA, "update_user.surql", query)?;
+    let variables = output
+        .split("export type UpdateUserVariables")
+        .nth(1)
+        .unwrap();
+
+    assert!(variables.contains("} & { [k: string]: unknown },"));
+
+    Ok(())
+}
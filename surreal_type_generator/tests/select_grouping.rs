@@ -1,5 +1,8 @@
+mod common;
+
 use pretty_assertions_sorted::assert_eq_sorted;
-use surreal_type_generator::{kind, QueryResult};
+use surreal_type_generator::{kind, Kind, QueryResult};
+use surrealdb::sql::Literal;
 
 #[test]
 fn select_group_by() -> anyhow::Result<()> {
@@ -25,7 +28,7 @@ DEFINE FIELD age ON user TYPE int;
         return_types,
         vec![kind!([kind!({
             name: kind!(String),
-            baz: kind!(Number)
+            baz: Kind::Literal(Literal::Number(5.into()))
         })]),]
     );
 
@@ -55,7 +58,7 @@ DEFINE FIELD name ON user TYPE string;
         return_types,
         vec![kind!([kind!({
             name: kind!(String),
-            total: kind!(Number)
+            total: kind!(Int)
         })])]
     );
 
@@ -82,9 +85,180 @@ DEFINE FIELD name ON user TYPE string;
     assert_eq_sorted!(
         return_types,
         vec![kind!([kind!({
-                total: kind!(Number)
+                total: kind!(Int)
+        })])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn select_group_by_math_aggregates() -> anyhow::Result<()> {
+    let query = r#"
+SELECT
+    name,
+    math::sum(age) as total_age,
+    math::max(age) as max_age,
+    math::mean(score) as mean_score
+FROM
+    user
+GROUP BY
+    name
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD age ON user TYPE int;
+DEFINE FIELD score ON user TYPE float;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            name: kind!(String),
+            total_age: kind!(Int),
+            max_age: kind!(Int),
+            mean_score: kind!(Float)
         })])]
     );
 
     Ok(())
 }
+
+#[test]
+fn math_functions_on_arrays() -> anyhow::Result<()> {
+    let query = r#"
+RETURN math::max(<array<datetime>> $dates);
+RETURN math::sum(<array<int>> $ints);
+RETURN math::sum(<array<int | float>> $numbers);
+RETURN math::abs(<decimal> $decimal);
+RETURN count(<array<bool>> $flags);
+"#;
+    let schema = r#"
+DEFINE TABLE placeholder SCHEMAFULL;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![
+            kind!(Opt(kind!(Datetime))),
+            kind!(Int),
+            kind!(Number),
+            kind!(Decimal),
+            kind!(Int),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn aggregate_view_fields_and_id() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE statement SCHEMAFULL;
+DEFINE FIELD content ON statement TYPE string;
+
+DEFINE TABLE statement_vote SCHEMAFULL;
+DEFINE FIELD rating ON statement_vote TYPE int;
+DEFINE FIELD statement ON statement_vote TYPE record<statement>;
+
+DEFINE TABLE statement_rating AS
+    SELECT
+        math::mean(rating) AS rating_avg,
+        math::sum(rating) AS rating_total,
+        count() AS total_votes,
+        statement
+    FROM statement_vote
+    GROUP BY statement;
+"#;
+    let query = r#"
+SELECT * FROM statement_rating;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            id: kind!(Record["statement_rating"]),
+            rating_avg: kind!(Decimal),
+            rating_total: kind!(Int),
+            total_votes: kind!(Int),
+            statement: kind!(Record["statement"])
+        })])]
+    );
+
+    let output = common::generate_typescript(schema, "statement_ratings.surql", query)?;
+
+    assert!(
+        output.contains("RecordId<\"statement_rating\"> & { id: Array<(RecordId<\"statement\">")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn aggregate_view_fields_match_grouped_select() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE vote SCHEMAFULL;
+DEFINE FIELD rating ON vote TYPE int;
+DEFINE FIELD weight ON vote TYPE float;
+DEFINE FIELD topic ON vote TYPE string;
+
+DEFINE TABLE topic_rating AS
+    SELECT
+        math::mean(rating) AS rating_avg,
+        math::sum(rating) AS rating_total,
+        math::max(weight) AS weight_max,
+        count() AS total_votes,
+        topic
+    FROM vote
+    GROUP BY topic;
+"#;
+    let query = r#"
+SELECT
+    math::mean(rating) AS rating_avg,
+    math::sum(rating) AS rating_total,
+    math::max(weight) AS weight_max,
+    count() AS total_votes,
+    topic
+FROM vote
+GROUP BY topic;
+
+SELECT * FROM topic_rating;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![
+            kind!([kind!({
+                rating_avg: kind!(Float),
+                rating_total: kind!(Int),
+                weight_max: kind!(Float),
+                total_votes: kind!(Int),
+                topic: kind!(String)
+            })]),
+            // views keep the same types, apart from the running mean which is a decimal
+            kind!([kind!({
+                id: kind!(Record["topic_rating"]),
+                rating_avg: kind!(Decimal),
+                rating_total: kind!(Int),
+                weight_max: kind!(Float),
+                total_votes: kind!(Int),
+                topic: kind!(String)
+            })])
+        ]
+    );
+
+    Ok(())
+}
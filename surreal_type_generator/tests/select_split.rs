@@ -0,0 +1,82 @@
+mod common;
+
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, QueryResult};
+
+const SCHEMA: &str = r#"
+DEFINE TABLE post SCHEMAFULL;
+DEFINE FIELD title ON post TYPE string;
+DEFINE FIELD tags ON post TYPE array<string>;
+DEFINE FIELD editors ON post TYPE option<array<record<user>>>;
+DEFINE FIELD meta ON post;
+DEFINE FIELD meta.scores ON post TYPE array<int>;
+DEFINE TABLE user SCHEMAFULL;
+"#;
+
+#[test]
+fn split_array_field() -> anyhow::Result<()> {
+    let query = r#"
+SELECT title, tags FROM post SPLIT tags;
+SELECT title, editors FROM post SPLIT editors;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![
+            kind!([kind!({
+                title: kind!(String),
+                tags: kind!(String)
+            })]),
+            kind!([kind!({
+                title: kind!(String),
+                editors: kind!(Opt(kind!(Record ["user"])))
+            })])
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn split_nested_array_field() -> anyhow::Result<()> {
+    let query = r#"
+SELECT title, tags, meta.scores FROM post SPLIT meta.scores, tags;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            title: kind!(String),
+            tags: kind!(String),
+            meta: kind!({
+                scores: kind!(Int)
+            })
+        })])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn split_non_array_field() -> anyhow::Result<()> {
+    for (query, expected_error) in [
+        (
+            "SELECT * FROM post SPLIT title;",
+            "Cannot split on `title` of type `string`, expected an array",
+        ),
+        (
+            "SELECT * FROM post SPLIT meta;",
+            "Cannot split on `meta` of type `{",
+        ),
+    ] {
+        common::assert_query_error(query, SCHEMA, expected_error);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, var_map, QueryResult};
+
+const SCHEMA: &str = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD age ON user TYPE option<int>;
+DEFINE FIELD tags ON user TYPE array<string>;
+DEFINE FIELD address ON user;
+DEFINE FIELD address.city ON user TYPE string;
+DEFINE FIELD created_at ON user TYPE datetime VALUE time::now();
+"#;
+
+#[test]
+fn set_parameter_inference() -> anyhow::Result<()> {
+    let query = r#"
+CREATE user SET name = $name, age = $age;
+UPDATE user SET address.city = $city;
+UPSERT user:john SET tags += $tag, created_at = $ignored;
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            name: kind!(String),
+            age: kind!(Opt(kind!(Int))),
+            city: kind!(String),
+            tag: kind!(String)
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn set_literal_values_are_checked() -> anyhow::Result<()> {
+    let query = r#"
+CREATE user SET name = "john", age = 5, tags = ["a"];
+UPDATE user SET tags += "b", tags -= ["a"], age = NONE;
+"#;
+
+    surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    for (query, expected_error) in [
+        (
+            "CREATE user SET name = 5;",
+            "Cannot set field `name` of type `string` to `5`",
+        ),
+        (
+            "UPDATE user SET tags += 5;",
+            "Cannot set field `tags` of type `array<string>` to `5`",
+        ),
+        (
+            "<int> $name; UPSERT user SET name = $name;",
+            "Cannot set field `name` of type `string` to `$name`",
+        ),
+        (
+            "UPDATE user SET nickname = 'johnny';",
+            "Field `nickname` does not exist on table `user`",
+        ),
+    ] {
+        common::assert_query_error(query, SCHEMA, expected_error);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn set_on_schemaless_table() -> anyhow::Result<()> {
+    let query = r#"
+CREATE post SET title = $title, anything = $anything;
+"#;
+    let schema = r#"
+DEFINE TABLE post SCHEMALESS;
+DEFINE FIELD title ON post TYPE string;
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            title: kind!(String),
+            anything: kind!(Any)
+        }
+    );
+
+    Ok(())
+}
@@ -1,5 +1,7 @@
+mod common;
+
 use pretty_assertions_sorted::assert_eq_sorted;
-use surreal_type_generator::{kind, QueryResult};
+use surreal_type_generator::{kind, var_map, QueryResult};
 
 #[test]
 fn update_statement_with_set_field() -> anyhow::Result<()> {
@@ -145,3 +147,90 @@ DEFINE FIELD baz ON user TYPE string;
 
     Ok(())
 }
+
+#[test]
+fn update_content_parameter_inference() -> anyhow::Result<()> {
+    let query = r#"
+UPDATE user:john CONTENT $user;
+"#;
+
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD email ON user TYPE option<string>;
+DEFINE FIELD created_at ON user TYPE datetime DEFAULT time::now() READONLY;
+DEFINE FIELD updated_at ON user TYPE datetime VALUE time::now();
+DEFINE FIELD role ON user TYPE string DEFAULT "member";
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            user: kind!({
+                name: kind!(String),
+                email: kind!(Opt(kind!(String))),
+                role: kind!(Opt(kind!(String)))
+            })
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn update_merge_parameter_inference() -> anyhow::Result<()> {
+    let query = r#"
+UPDATE user MERGE $patch;
+"#;
+
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD address ON user;
+DEFINE FIELD address.city ON user TYPE string;
+DEFINE FIELD address.zip ON user TYPE option<string>;
+DEFINE FIELD created_at ON user TYPE datetime DEFAULT time::now() READONLY;
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            patch: kind!({
+                name: kind!(Opt(kind!(String))),
+                address: kind!(Opt(kind!({
+                    city: kind!(Opt(kind!(String))),
+                    zip: kind!(Opt(kind!(String)))
+                })))
+            })
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn update_content_rejects_readonly_fields() -> anyhow::Result<()> {
+    let query = r#"
+UPDATE user:john CONTENT { name: $name, created_at: $created_at };
+"#;
+
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD created_at ON user TYPE datetime DEFAULT time::now() READONLY;
+"#;
+
+    common::assert_query_error(
+        query,
+        schema,
+        "Field `created_at` is readonly on table `user`",
+    );
+
+    Ok(())
+}
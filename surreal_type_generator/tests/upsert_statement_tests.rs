@@ -131,3 +131,36 @@ DEFINE FIELD age ON user TYPE number;
         })])]
     );
 }
+
+#[test]
+fn upsert_merge_parameter_inference() -> anyhow::Result<()> {
+    let query = r#"
+UPSERT user:john MERGE $patch;
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD address ON user;
+DEFINE FIELD address.city ON user TYPE string;
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    let user_merge = kind!({
+        id: kind!(Opt(kind!(Record ["user"]))),
+        name: kind!(Opt(kind!(String))),
+        address: kind!(Opt(kind!({
+            city: kind!(Opt(kind!(String)))
+        })))
+    });
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            patch: kind!(Either [user_merge.clone(), kind!([user_merge])])
+        }
+    );
+
+    Ok(())
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, var_map, QueryResult};
+
+const SCHEMA: &str = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD email ON user TYPE option<string>;
+DEFINE FIELD age ON user TYPE int;
+DEFINE FIELD tags ON user TYPE array<string>;
+DEFINE FIELD best_friend ON user TYPE record<user>;
+"#;
+
+#[test]
+fn select_where_comparison_inference() -> anyhow::Result<()> {
+    let query = r#"
+SELECT * FROM user WHERE name = $name AND (age > $min_age OR email != $email);
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            name: kind!(String),
+            min_age: kind!(Int),
+            email: kind!(Opt(kind!(String)))
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn where_array_operator_inference() -> anyhow::Result<()> {
+    let query = r#"
+SELECT * FROM user WHERE tags CONTAINS $tag;
+DELETE user WHERE $other_tag IN tags;
+UPDATE user SET age = 5 WHERE best_friend IN $friends;
+UPSERT user SET age = 5 WHERE tags CONTAINSANY $any_tags;
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            tag: kind!(String),
+            other_tag: kind!(String),
+            friends: kind!([kind!(Record ["user"])]),
+            any_tags: kind!([kind!(String)])
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn where_does_not_override_typed_params() -> anyhow::Result<()> {
+    let query = r#"
+<string> $name;
+LET $age = 5;
+
+SELECT * FROM user WHERE email = $name AND age = $age;
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            name: kind!(String)
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn where_conflicting_inference_is_reported() -> anyhow::Result<()> {
+    let query = r#"
+SELECT * FROM user WHERE name = $value;
+SELECT * FROM user WHERE age = $value;
+"#;
+
+    common::assert_query_error(
+        query,
+        SCHEMA,
+        "Conflicting types inferred for parameter `$value`",
+    );
+
+    Ok(())
+}
+
+#[test]
+fn where_compatible_inference_keeps_narrower_type() -> anyhow::Result<()> {
+    let query = r#"
+SELECT * FROM user WHERE email = $email;
+SELECT * FROM user WHERE name = $email;
+SELECT * FROM user WHERE age = $age;
+RETURN math::abs($age);
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            email: kind!(String),
+            age: kind!(Int)
+        }
+    );
+
+    Ok(())
+}